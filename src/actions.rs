@@ -6,7 +6,7 @@
 //! This module also exposes a higher level abstraction of so-called `PlayerAction`s,
 //! which are a player's way of interacting.
 use crate::deck::Card;
-use crate::ChipCount;
+use crate::{BettingStructure, ChipCount, GameVariant};
 
 /// An `Action` is internally used to alter the game state. Using a stream of
 /// actions each round of poker played can be recovered completely.
@@ -25,13 +25,57 @@ pub enum Action {
         big_blind: ChipCount,
         /// The size of the small blind for this round.
         small_blind: ChipCount,
+        /// The position of the dealer in this round.
+        dealer_position: usize,
+        /// The stacks of all the players (indexed by position) before any chips were placed.
+        player_stacks: Vec<ChipCount>,
+        /// The betting structure of this round.
+        betting_structure: BettingStructure,
+        /// The game variant of this round.
+        variant: GameVariant,
+        /// The size of the ante for this round, zero if no antes are paid.
+        ante: ChipCount,
+        /// The amount each player posted if this round is a bomb pot, see `RoundOptions`.
+        bomb_pot: Option<ChipCount>,
     },
     /// Indicates that the blind size increased by the associated chip count.
     IncreaseBlind(ChipCount),
     /// Indicates that the player at the given location paid a blind of the given size.
     Blind(usize, ChipCount),
+    /// Indicates that the player at the given location posted a straddle of the given size.
+    Straddle(usize, ChipCount),
+    /// Indicates that the player at the given location returned after missing the big blind and
+    /// posted the missed blinds of the given total size, see `MissedBlinds::PostBoth`.
+    ///
+    /// The small blind is dead, i. e. it does not count as a bet of the player.
+    MissedBlinds(usize, ChipCount),
+    /// Indicates that the player at the given location sits out this round.
+    SitOut(usize),
+    /// Indicates that the player at the given location returned after sitting out.
+    Return(usize),
+    /// Indicates that the player at the given location did not decide in time, see
+    /// `TimedPlayer`. The action taken instead follows.
+    Timeout(usize),
+    /// Indicates that the player at the given location paid an ante of the given size.
+    ///
+    /// If the player's stack does not cover the ante, the size corresponds to the stack.
+    Ante(usize, ChipCount),
+    /// Indicates that the player at the given location brought in for the given amount of chips.
+    ///
+    /// This is only used in stud games.
+    BringIn(usize, ChipCount),
     /// Indicates that the player at given location was dealt the given hand.
-    DealHand(usize, [Card; 2]),
+    ///
+    /// In stud games these are the cards dealt face down before the first up card.
+    DealHand(usize, Vec<Card>),
+    /// Indicates that the player at the given location was dealt the given card face up.
+    ///
+    /// This is only used in stud games.
+    DealUp(usize, Card),
+    /// Indicates that the player at the given location was dealt the given card face down.
+    ///
+    /// This is only used in stud games, for the last card dealt.
+    DealDown(usize, Card),
     /// Indicates that the given cards were dealt as flop cards.
     DealFlop([Card; 3]),
     /// Indicates that the given card was played as the turn card.
@@ -52,6 +96,17 @@ pub enum Action {
     Fold(usize),
     /// Indicates that the players at the given locations won the given amount of chips.
     Win(Vec<(usize, ChipCount)>),
+    /// Indicates that the given action happened in the run with the given index if the rest of
+    /// the board is run multiple times.
+    ///
+    /// Only `DealFlop`, `DealTurn`, `DealRiver` and `Win` actions are tagged. Each run deals
+    /// the cards missing from the board and ends with the wins of this run.
+    Run(usize, Box<Action>),
+    /// Indicates that the given action applies to the second board of a double board game.
+    ///
+    /// Only `DealFlop`, `DealTurn` and `DealRiver` actions are tagged. Each street of the second
+    /// board is dealt right after the same street of the first board.
+    SecondBoard(Box<Action>),
 }
 
 /// An action a player can cause.
@@ -69,6 +124,14 @@ pub enum PlayerAction {
     ///
     /// The given chip count should not be increased.
     Blind(ChipCount),
+    /// Indicates that the player has to pay an ante of the given size.
+    ///
+    /// The given chip count should not be increased.
+    Ante(ChipCount),
+    /// Indicates that the player has to bring in for the given amount in a stud game.
+    ///
+    /// The given chip count should not be increased.
+    BringIn(ChipCount),
     /// Indicates that the player may check.
     Check,
     /// Indicates that the player may call the given amount.
@@ -117,6 +180,9 @@ impl Action {
     ) -> Self {
         match player_action {
             PlayerAction::Blind(c) => validated!(player_stack, player_position, c, Blind),
+            // antes are never considered as an all-in
+            PlayerAction::Ante(c) => Action::Ante(player_position, c.min(player_stack)),
+            PlayerAction::BringIn(c) => validated!(player_stack, player_position, c, BringIn),
             PlayerAction::Check => Action::Check(player_position),
             PlayerAction::Call(c) => validated!(player_stack, player_position, c, Call),
             PlayerAction::Raise(c) => validated!(player_stack, player_position, c, Raise),
@@ -125,4 +191,29 @@ impl Action {
             PlayerAction::Fold => Action::Fold(player_position),
         }
     }
+
+    /// Whether the player at the given position may see this action, i. e. cards dealt face down
+    /// are only visible to the player who received them.
+    pub(crate) fn is_visible_to(&self, position: usize) -> bool {
+        match self {
+            Action::DealHand(pos, _) | Action::DealDown(pos, _) => *pos == position,
+            _ => true,
+        }
+    }
+}
+
+impl PlayerAction {
+    /// Whether this action is one of the given possible actions, see `Player::act`.
+    ///
+    /// Bets and raises may exceed the minimum amount, they are reduced to the maximum allowed
+    /// when taken. All other amounts have to match.
+    pub(crate) fn is_allowed(&self, possible_actions: &[PlayerAction]) -> bool {
+        possible_actions
+            .iter()
+            .any(|possible| match (self, possible) {
+                (PlayerAction::Bet(c), PlayerAction::Bet(min))
+                | (PlayerAction::Raise(c), PlayerAction::Raise(min)) => c >= min,
+                _ => self == possible,
+            })
+    }
 }
@@ -0,0 +1,286 @@
+//! This module exposes players which decide asynchronously, i. e. by calling out to network
+//! services, and the round driving them.
+//!
+//! The async round is runtime-agnostic, decisions are awaited in `AsyncRound::next` without
+//! blocking the thread. All other steps are taken synchronously just like in `Round`.
+use crate::actions::{Action, PlayerAction};
+use crate::play::{Round, RoundCheckpoint};
+use crate::player::Player;
+use crate::state::TransparentState;
+use crate::summary::RoundSummary;
+use crate::ChipCount;
+
+use std::future::Future;
+use std::ops::DerefMut;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+/// The future of a decision of an `AsyncPlayer`.
+pub type Decision<'a> = Pin<Box<dyn Future<Output = PlayerAction> + Send + 'a>>;
+
+/// The asynchronous counterpart of `Player`.
+///
+/// Only decisions are taken asynchronously, all other functions are called synchronously and
+/// have the same meaning as in `Player`. Async players are seated at a table using `AsyncSeat`.
+pub trait AsyncPlayer {
+    /// This function gets called whenever the player is seated at a table.
+    fn init(&mut self, position: usize, initial_stack: ChipCount);
+
+    /// This function gets called everytime the player has to decide, the decision is awaited.
+    ///
+    /// Forced bets are posted without asking the player.
+    fn act<'a>(
+        &'a mut self,
+        state: &'a TransparentState,
+        possible_actions: &'a [PlayerAction],
+    ) -> Decision<'a>;
+
+    /// See `Player::straddle`.
+    fn straddle(&mut self, _state: &TransparentState, _size: ChipCount) -> bool {
+        false
+    }
+
+    /// See `Player::run_count`.
+    fn run_count(&mut self, _state: &TransparentState, _max_runs: usize) -> usize {
+        1
+    }
+
+    /// See `Player::rebuy`.
+    fn rebuy(
+        &mut self,
+        _state: &TransparentState,
+        _min_buy_in: ChipCount,
+        _max_buy_in: ChipCount,
+    ) -> Option<ChipCount> {
+        None
+    }
+
+    /// See `Player::sit_out`.
+    fn sit_out(&mut self, _state: &TransparentState, sitting_out: bool) -> bool {
+        sitting_out
+    }
+
+    /// See `Player::observe`.
+    fn observe(&mut self, _action: &Action) {}
+
+    /// See `Player::round_finished`.
+    fn round_finished(&mut self, _summary: &RoundSummary) {}
+
+    /// This function gets called when the player lost all the chips and has to leave the table.
+    fn bust(&mut self);
+}
+
+/// Seats an `AsyncPlayer` at a table.
+///
+/// Rounds at the table are played asynchronously using `Table::play_one_round_async`. In
+/// synchronous rounds the decisions of the player block the thread until they are taken.
+#[derive(Debug, Clone)]
+pub struct AsyncSeat<A>(pub A);
+
+impl<A: AsyncPlayer> Player for AsyncSeat<A> {
+    fn init(&mut self, position: usize, initial_stack: ChipCount) {
+        self.0.init(position, initial_stack);
+    }
+
+    fn act(&mut self, state: &TransparentState, possible_actions: &[PlayerAction]) -> PlayerAction {
+        // forced bets leave nothing to decide
+        match possible_actions {
+            [action] => *action,
+            _ => block_on(self.0.act(state, possible_actions)),
+        }
+    }
+
+    fn straddle(&mut self, state: &TransparentState, size: ChipCount) -> bool {
+        self.0.straddle(state, size)
+    }
+
+    fn run_count(&mut self, state: &TransparentState, max_runs: usize) -> usize {
+        self.0.run_count(state, max_runs)
+    }
+
+    fn rebuy(
+        &mut self,
+        state: &TransparentState,
+        min_buy_in: ChipCount,
+        max_buy_in: ChipCount,
+    ) -> Option<ChipCount> {
+        self.0.rebuy(state, min_buy_in, max_buy_in)
+    }
+
+    fn sit_out(&mut self, state: &TransparentState, sitting_out: bool) -> bool {
+        self.0.sit_out(state, sitting_out)
+    }
+
+    fn observe(&mut self, action: &Action) {
+        self.0.observe(action);
+    }
+
+    fn round_finished(&mut self, summary: &RoundSummary) {
+        self.0.round_finished(summary);
+    }
+
+    fn bust(&mut self) {
+        self.0.bust();
+    }
+}
+
+/// The asynchronous counterpart of `Round`.
+///
+/// Each step taken by `next` corresponds to one step taken in the round played, decisions of the
+/// players are awaited.
+pub struct AsyncRound<'a, A: AsyncPlayer, T: DerefMut<Target = TransparentState>> {
+    round: Round<'a, AsyncSeat<A>, T>,
+}
+
+impl<'a, A: AsyncPlayer, T: DerefMut<Target = TransparentState>> AsyncRound<'a, A, T> {
+    pub(crate) fn new(round: Round<'a, AsyncSeat<A>, T>) -> Self {
+        Self {
+            round: round.with_external_decisions(),
+        }
+    }
+
+    /// Progresses the state of the round one step ahead, returns `None` once the round ended.
+    ///
+    /// All actions taken so far are mirrored into the underlying `TransparentState`.
+    pub async fn next(&mut self) -> Option<Action> {
+        loop {
+            if let Some(action) = Iterator::next(&mut self.round) {
+                return Some(action);
+            }
+            // the round pauses whenever a player has to decide
+            let position = self.round.next_to_act()?;
+            let (players, state) = self.round.split();
+            let player = &mut players[position];
+            let possible_actions = state.possible_actions(position);
+            let decision = player.0.act(state, &possible_actions).await;
+            self.round.decide(decision);
+        }
+    }
+
+    /// Clones this state into a `RoundCheckpoint`, see `Round::create_checkpoint`.
+    pub fn create_checkpoint(&self) -> RoundCheckpoint {
+        self.round.create_checkpoint()
+    }
+
+    /// Inspect the underlying `TransparentState` before stepping to the next action.
+    pub fn inspect_state(&self) -> &'_ TransparentState {
+        self.round.inspect_state()
+    }
+}
+
+/// Wakes the thread waiting for a future in `block_on`.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs the given future to completion on the current thread.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::CardCollection;
+    use crate::mock::MockPlayer;
+    use crate::table::{BlindPolicy, Table};
+
+    /// A future which is pending once before it is ready, like a response from a network service.
+    struct Response(Option<PlayerAction>, bool);
+
+    impl Future for Response {
+        type Output = PlayerAction;
+
+        fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<PlayerAction> {
+            if self.1 {
+                Poll::Ready(self.0.take().unwrap())
+            } else {
+                self.1 = true;
+                context.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    /// A player which takes the decisions of the mock player asynchronously.
+    struct RemotePlayer(MockPlayer);
+
+    impl AsyncPlayer for RemotePlayer {
+        fn init(&mut self, position: usize, initial_stack: ChipCount) {
+            self.0.init(position, initial_stack);
+        }
+
+        fn act<'a>(
+            &'a mut self,
+            state: &'a TransparentState,
+            possible_actions: &'a [PlayerAction],
+        ) -> Decision<'a> {
+            let decision = self.0.act(state, possible_actions);
+            Box::pin(Response(Some(decision), false))
+        }
+
+        fn bust(&mut self) {}
+    }
+
+    fn table() -> Table<AsyncSeat<RemotePlayer>> {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Raise(10)]), // dealer
+            // forced bets are posted without asking the player
+            MockPlayer::new(vec![PlayerAction::Fold]),
+            MockPlayer::new(vec![PlayerAction::Fold]),
+        ];
+        let players = players
+            .into_iter()
+            .map(|player| AsyncSeat(RemotePlayer(player)));
+        Table::new(players, 100, 1, BlindPolicy::NeverIncrease)
+    }
+
+    #[test]
+    fn test_play_async_round() {
+        let mut table = table();
+        let mut round = table
+            .play_one_round_async(CardCollection::default())
+            .unwrap();
+        let mut actions = Vec::new();
+        while let Some(action) = block_on(round.next()) {
+            actions.push(action);
+        }
+
+        assert_eq!(
+            actions[4..],
+            [
+                Action::Blind(1, 1),
+                Action::Blind(2, 2),
+                Action::Raise(0, 10),
+                Action::Fold(1),
+                Action::Fold(2),
+                Action::Win(vec![(0, 13)]),
+            ]
+        );
+        assert_eq!(round.inspect_state().actions, actions);
+    }
+
+    #[test]
+    fn test_async_seat_blocks_in_round() {
+        let mut table = table();
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+        assert_eq!(actions.last(), Some(&Action::Win(vec![(0, 13)])));
+    }
+}
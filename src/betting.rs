@@ -0,0 +1,57 @@
+//! This module exposes the betting structures supported, i. e. the rules which limit the size of bets and raises,
+//! as well as the optional straddles and the rules for blinds missed while sitting out.
+
+/// Exposes variants to handle betting structures.
+///
+/// The betting structure of a table can be selected using `Table::with_betting_structure`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BettingStructure {
+    /// Bets and raises are only limited by the stack of the player.
+    #[default]
+    NoLimit,
+    /// Bets and raises are limited by the size of the pot.
+    ///
+    /// The maximum number of chips a player may place is the amount required to call plus
+    /// the size of the pot after calling.
+    PotLimit,
+    /// Bets and raises are of a fixed size.
+    ///
+    /// Before the turn is dealt the size of a bet is equal to the big blind (small bet),
+    /// afterwards it is twice the big blind (big bet).
+    FixedLimit {
+        /// The maximum number of bets and raises per street, including the initial bet.
+        /// Pre-flop the big blind counts as the initial bet. Usually this is 4.
+        ///
+        /// The cap is lifted if only two players are remaining.
+        raise_cap: usize,
+    },
+}
+
+/// Exposes the kinds of straddles which may be offered to the players.
+///
+/// A straddle is an optional blind of twice the big blind, which is posted before the cards are
+/// seen. The straddler acts last pre-flop and the minimum raise is based on the straddle. Straddles
+/// can be enabled using `Table::with_straddle`, they are only offered if at least three players are
+/// dealt in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Straddle {
+    /// The player left of the big blind (under the gun) may straddle. The pre-flop action starts
+    /// left of the straddler.
+    UnderTheGun,
+    /// The player on the button may straddle (Mississippi straddle). The pre-flop action starts
+    /// with the small blind.
+    Button,
+}
+
+/// Exposes the rules for players returning after they missed the big blind while sitting out.
+///
+/// See `Table::set_sitting_out` and `Table::with_missed_blinds`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MissedBlinds {
+    /// The returning player is dealt in right away and posts the missed blinds after the big
+    /// blind, i. e. a dead small blind and a live big blind.
+    #[default]
+    PostBoth,
+    /// The returning player is not dealt in until it is the player's turn to post the big blind.
+    WaitForBigBlind,
+}
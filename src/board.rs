@@ -1,9 +1,9 @@
-use crate::deck::{Card, Rank, Rankable};
+use crate::deck::{Card, Rankable};
 
 /// A structure representing the current state of the board, i. e. the public cards
 #[derive(Debug, Copy, Clone)]
 pub struct Board {
-    cards: [Card; 7],
+    cards: [Card; 5],
     n: usize,
 }
 
@@ -20,8 +20,6 @@ impl Board {
                 default_card,
                 default_card,
                 default_card,
-                default_card,
-                default_card,
             ],
             n: 0,
         }
@@ -84,12 +82,9 @@ impl Board {
     pub(crate) fn clear(&mut self) {
         self.n = 0;
     }
-    pub(crate) fn rank_hand(&mut self, hand: [Card; 2]) -> Rank {
-        debug_assert_eq!(self.n, 5);
-
-        self.cards[5] = hand[0];
-        self.cards[6] = hand[1];
-        self.rank()
+    /// Removes all but the first `n` cards from the board.
+    pub(crate) fn truncate(&mut self, n: usize) {
+        self.n = std::cmp::min(self.n, n);
     }
 }
 
@@ -101,6 +96,6 @@ impl Default for Board {
 
 impl Rankable for Board {
     fn cards(&self) -> &[Card] {
-        &self.cards
+        self.all_cards()
     }
 }
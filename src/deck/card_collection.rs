@@ -11,6 +11,26 @@ pub struct CardCollection {
 }
 
 impl CardCollection {
+    /// Return a short deck consisting of 36 cards, i. e. a default deck without the cards from
+    /// two to five.
+    ///
+    /// This deck is used for short deck (6+) Hold'em.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use poppy::deck::CardCollection;
+    /// assert_eq!(CardCollection::short_deck().len(), 36);
+    /// ```
+    pub fn short_deck() -> Self {
+        let cards = CardCollection::default()
+            .cards
+            .into_iter()
+            .filter(|c| c.value >= Value::Six)
+            .collect();
+        CardCollection { cards }
+    }
+
     /// Shuffle this card collection using the given random number generator.
     ///
     /// `rng(x)` should return a random number in range `[0, x)`
@@ -26,7 +46,7 @@ impl CardCollection {
     #[cfg(test)]
     pub(crate) fn to_array<A: Default + AsMut<[Card]>>(&self) -> A {
         let mut a = A::default();
-        <A as AsMut<[Card]>>::as_mut(&mut a).clone_from_slice(&self);
+        <A as AsMut<[Card]>>::as_mut(&mut a).clone_from_slice(self);
         a
     }
 }
@@ -96,7 +116,7 @@ impl TryFrom<&str> for CardCollection {
             // Now try and get a char.
             let vco = chars.next();
             // If there was no char then we are done.
-            if vco == None {
+            if vco.is_none() {
                 break;
             } else {
                 // If we got a value char then we should get a
@@ -111,7 +131,7 @@ impl TryFrom<&str> for CardCollection {
                     .ok_or_else(|| format!("Couldn't parse suit {}", sco.unwrap_or('?')))?;
 
                 let c = Card { value: v, suit: s };
-                if cards.iter().any(|&card| card == c) {
+                if cards.contains(&c) {
                     // If this card is already in the set then error out.
                     return Err(format!("This card has already been added {}", c));
                 } else {
@@ -120,7 +140,7 @@ impl TryFrom<&str> for CardCollection {
             }
         }
 
-        if chars.next() != None {
+        if chars.next().is_some() {
             return Err(String::from("Extra un-used chars found."));
         }
 
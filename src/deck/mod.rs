@@ -5,7 +5,10 @@ mod rank;
 
 pub use card::Card;
 pub use card_collection::CardCollection;
-pub use rank::{Rank, Rankable};
+pub use rank::{
+    cmp_short_deck, rank_ace_to_five, rank_deuce_to_seven, rank_omaha, rank_omaha_low,
+    rank_short_deck, LowRank, Rank, Rankable,
+};
 
 /// A trait representing a default card deck.
 pub trait Deck {
@@ -17,7 +17,8 @@
 //! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
-use super::card::Card;
+use super::card::{Card, Value};
+use std::cmp::Ordering;
 
 /// All the different possible hand ranks.
 /// For each hand rank the u32 corresponds to
@@ -210,6 +211,42 @@ pub trait Rankable {
         }
     }
 
+    /// Rank the cards to find the best ace-to-five low hand, i. e. the way hands are ranked
+    /// in Razz. See `rank_ace_to_five` for details.
+    ///
+    /// This will work on 5 cards or more.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::convert::TryInto;
+    /// use poppy::deck::{CardCollection, Rankable};
+    ///
+    /// let seven: CardCollection = "7c5d4h3s2cKdKh".try_into().unwrap();
+    /// let eight: CardCollection = "8c5d4h3s2cKdQh".try_into().unwrap();
+    /// assert!(seven.rank_ace_to_five_low() > eight.rank_ace_to_five_low());
+    /// ```
+    fn rank_ace_to_five_low(&self) -> LowRank {
+        best_low_hand(self.cards(), rank_ace_to_five)
+    }
+
+    /// Rank the cards to find the best deuce-to-seven low hand. See `rank_deuce_to_seven`
+    /// for details.
+    ///
+    /// This will work on 5 cards or more.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::convert::TryInto;
+    /// use poppy::deck::{CardCollection, Rankable};
+    ///
+    /// let seven: CardCollection = "7c5d4h3s2c".try_into().unwrap();
+    /// let wheel: CardCollection = "5c4d3h2sAc".try_into().unwrap();
+    /// assert!(seven.rank_deuce_to_seven_low() > wheel.rank_deuce_to_seven_low());
+    /// ```
+    fn rank_deuce_to_seven_low(&self) -> LowRank {
+        best_low_hand(self.cards(), rank_deuce_to_seven)
+    }
+
     /// Rank this hand. It doesn't do any caching so it's left up to the user
     /// to understand that duplicate work will be done if this is called more than once.
     fn rank_five(&self) -> Rank {
@@ -308,9 +345,297 @@ impl Rankable for &[Card] {
     }
 }
 
+/// Bit mask for the lowest straight in short deck (Ace, six, seven, eight, nine)
+const SHORT_DECK_WHEEL: u32 = 0b1_0000_1111_0000;
+
+/// Rank the cards to find the best 5 card hand of short deck (6+) poker.
+///
+/// In short deck A-6-7-8-9 forms the lowest straight. Use `cmp_short_deck` to compare the
+/// returned ranks, since a flush beats a full house.
+///
+/// # Examples
+/// ```
+/// use std::convert::TryInto;
+/// use poppy::deck::{rank_short_deck, CardCollection, Rank};
+///
+/// let hand: CardCollection = "As6d7c8h9sKdKh".try_into().unwrap();
+/// assert_eq!(rank_short_deck(&hand), Rank::Straight(0));
+/// ```
+pub fn rank_short_deck(cards: &[Card]) -> Rank {
+    let rank = cards.rank();
+    match rank {
+        Rank::HighCard(_) | Rank::OnePair(_) | Rank::TwoPair(_) | Rank::ThreeOfAKind(_) => {
+            let value_set = cards.iter().fold(0, |set, c| set | 1 << c.value as u32);
+            if value_set & SHORT_DECK_WHEEL == SHORT_DECK_WHEEL {
+                Rank::Straight(0)
+            } else {
+                rank
+            }
+        }
+        Rank::Flush(_) => {
+            // the flush may contain the lowest straight flush
+            let suit_value_sets = cards.iter().fold([0u32; 4], |mut sets, c| {
+                sets[c.suit as usize] |= 1 << c.value as u32;
+                sets
+            });
+            if suit_value_sets
+                .iter()
+                .any(|&set| set & SHORT_DECK_WHEEL == SHORT_DECK_WHEEL)
+            {
+                Rank::StraightFlush(0)
+            } else {
+                rank
+            }
+        }
+        // any other made hand beats the lowest straight
+        _ => rank,
+    }
+}
+
+/// Compare two ranks created by `rank_short_deck`, i. e. with a flush beating a full house.
+///
+/// # Examples
+/// ```
+/// use poppy::deck::{cmp_short_deck, Rank};
+/// use std::cmp::Ordering;
+///
+/// assert_eq!(
+///     cmp_short_deck(&Rank::Flush(0), &Rank::FullHouse(u32::MAX)),
+///     Ordering::Greater
+/// );
+/// ```
+pub fn cmp_short_deck(a: &Rank, b: &Rank) -> Ordering {
+    fn order(rank: &Rank) -> (u8, u32) {
+        match *rank {
+            Rank::HighCard(v) => (0, v),
+            Rank::OnePair(v) => (1, v),
+            Rank::TwoPair(v) => (2, v),
+            Rank::ThreeOfAKind(v) => (3, v),
+            Rank::Straight(v) => (4, v),
+            Rank::FullHouse(v) => (5, v),
+            Rank::Flush(v) => (6, v),
+            Rank::FourOfAKind(v) => (7, v),
+            Rank::StraightFlush(v) => (8, v),
+        }
+    }
+    order(a).cmp(&order(b))
+}
+
+/// Rank the best Omaha hand, i. e. the best five card hand which uses exactly two of the
+/// given hole cards and exactly three cards of the board.
+///
+/// Every combination is ranked using `Rankable::rank_five`.
+///
+/// # Examples
+/// ```
+/// use std::convert::TryInto;
+/// use poppy::deck::{rank_omaha, CardCollection, Rank};
+///
+/// let hand: CardCollection = "AsKs7h2d".try_into().unwrap();
+/// let board: CardCollection = "Qs9s4s7c7d".try_into().unwrap();
+/// // the flush is made with two spades in hand, a single seven does not make a full house
+/// let rank = rank_omaha(&hand, &board);
+/// assert!(Rank::Flush(0) <= rank);
+/// assert!(Rank::FullHouse(0) > rank);
+/// ```
+pub fn rank_omaha(hand: &[Card], board: &[Card]) -> Rank {
+    omaha_hands(hand, board)
+        .map(|cards| (&cards[..]).rank_five())
+        .max()
+        .expect("At least two hole cards and three board cards are required")
+}
+
+/// Enumerate all five card hands which use exactly two of the hole cards and exactly three
+/// cards of the board.
+fn omaha_hands(hand: &[Card], board: &[Card]) -> impl Iterator<Item = [Card; 5]> {
+    let mut hands = Vec::new();
+    for (i, &h1) in hand.iter().enumerate() {
+        for &h2 in hand[i + 1..].iter() {
+            for (j, &b1) in board.iter().enumerate() {
+                for (k, &b2) in board.iter().enumerate().skip(j + 1) {
+                    for &b3 in board[k + 1..].iter() {
+                        hands.push([h1, h2, b1, b2, b3]);
+                    }
+                }
+            }
+        }
+    }
+    hands.into_iter()
+}
+
+/// The rank of a hand in games where the lowest hand wins.
+///
+/// Just like with `Rank` a better hand compares greater, i. e. the best low hand is the
+/// maximum of all low ranks.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LowRank(u32);
+
+impl LowRank {
+    /// Check whether this hand qualifies for the low half of the pot in eight-or-better games,
+    /// i. e. whether it consists of five unpaired cards which are eight or lower.
+    ///
+    /// This only applies to ranks created by `rank_ace_to_five`.
+    pub fn is_eight_or_better(&self) -> bool {
+        // without pairs the highest card is stored in the top bits
+        self.0 >> 16 <= Value::Eight as u32 + 1
+    }
+}
+
+impl Ord for LowRank {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // the hand with the lower value is the better one
+        other.0.cmp(&self.0)
+    }
+}
+
+impl PartialOrd for LowRank {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Rank five cards as an ace-to-five low hand.
+///
+/// Aces are low and straights and flushes do not count, hence the best possible hand is
+/// A-2-3-4-5 (the wheel). Paired hands are worse than any unpaired hand.
+///
+/// # Examples
+/// ```
+/// use std::convert::TryInto;
+/// use poppy::deck::{rank_ace_to_five, CardCollection};
+///
+/// let wheel: CardCollection = "5h4h3h2hAh".try_into().unwrap();
+/// let eight: CardCollection = "8d6c4s2hAc".try_into().unwrap();
+/// let pair: CardCollection = "AdAc2s3h4c".try_into().unwrap();
+/// assert!(rank_ace_to_five(&wheel) > rank_ace_to_five(&eight));
+/// assert!(rank_ace_to_five(&eight) > rank_ace_to_five(&pair));
+/// assert!(rank_ace_to_five(&eight).is_eight_or_better());
+/// ```
+pub fn rank_ace_to_five(cards: &[Card]) -> LowRank {
+    debug_assert_eq!(cards.len(), 5);
+
+    // aces are the lowest cards
+    let mut value_to_count = [0u8; 13];
+    for c in cards {
+        value_to_count[(c.value as usize + 1) % 13] += 1;
+    }
+
+    // sort by count first, then by value, i. e. the way hands are compared
+    let mut values: Vec<(u8, usize)> = value_to_count
+        .iter()
+        .enumerate()
+        .filter(|(_, &count)| count > 0)
+        .map(|(value, &count)| (count, value))
+        .collect();
+    values.sort_unstable_by(|a, b| b.cmp(a));
+
+    // no pair, one pair, two pair, three of a kind, full house, four of a kind
+    let category = match (values[0].0, values.get(1).map(|v| v.0)) {
+        (1, _) => 0,
+        (2, Some(2)) => 2,
+        (2, _) => 1,
+        (3, Some(2)) => 4,
+        (3, _) => 3,
+        _ => 5,
+    };
+
+    let mut rank = category;
+    for (count, value) in values {
+        for _ in 0..count {
+            rank = rank << 4 | value as u32;
+        }
+    }
+    LowRank(rank)
+}
+
+/// Rank five cards as a deuce-to-seven low hand.
+///
+/// Aces are always high and straights and flushes count against the hand, hence the best
+/// possible hand is 7-5-4-3-2 in different suits. A-2-3-4-5 is no straight but an ace high hand.
+///
+/// The ranks are not comparable with ranks created by `rank_ace_to_five`.
+///
+/// # Examples
+/// ```
+/// use std::convert::TryInto;
+/// use poppy::deck::{rank_deuce_to_seven, CardCollection};
+///
+/// let seven: CardCollection = "7d5c4s3h2c".try_into().unwrap();
+/// let flush: CardCollection = "7c5c4c3c2c".try_into().unwrap();
+/// let straight: CardCollection = "6d5c4s3h2c".try_into().unwrap();
+/// let pair: CardCollection = "2d2c4s3h5c".try_into().unwrap();
+/// assert!(rank_deuce_to_seven(&seven) > rank_deuce_to_seven(&pair));
+/// assert!(rank_deuce_to_seven(&pair) > rank_deuce_to_seven(&straight));
+/// assert!(rank_deuce_to_seven(&straight) > rank_deuce_to_seven(&flush));
+/// ```
+pub fn rank_deuce_to_seven(cards: &[Card]) -> LowRank {
+    debug_assert_eq!(cards.len(), 5);
+
+    // the ace is always high, hence the wheel is no straight
+    let (category, value) = match cards.rank_five() {
+        Rank::Straight(0) => (0, WHEEL),
+        Rank::StraightFlush(0) => (5, WHEEL),
+        Rank::HighCard(value) => (0, value),
+        Rank::OnePair(value) => (1, value),
+        Rank::TwoPair(value) => (2, value),
+        Rank::ThreeOfAKind(value) => (3, value),
+        Rank::Straight(value) => (4, value),
+        Rank::Flush(value) => (5, value),
+        Rank::FullHouse(value) => (6, value),
+        Rank::FourOfAKind(value) => (7, value),
+        Rank::StraightFlush(value) => (8, value),
+    };
+    // values of any category take up to 26 bits
+    LowRank(category << 26 | value)
+}
+
+/// Ranks the best low hand which can be made of five of the given cards using the given
+/// function to rank five cards.
+fn best_low_hand(cards: &[Card], rank_five: fn(&[Card]) -> LowRank) -> LowRank {
+    assert!(cards.len() >= 5 && cards.len() < 32);
+
+    // each set of five bits corresponds to one hand
+    (0u32..1 << cards.len())
+        .filter(|mask| mask.count_ones() == 5)
+        .map(|mask| {
+            let hand: Vec<Card> = (0..cards.len())
+                .filter(|i| mask & 1 << i != 0)
+                .map(|i| cards[i])
+                .collect();
+            rank_five(&hand)
+        })
+        .max()
+        .unwrap()
+}
+
+/// Rank the best qualifying Omaha low hand, i. e. the best eight-or-better ace-to-five low
+/// hand which uses exactly two of the given hole cards and exactly three cards of the board.
+///
+/// Returns `None` if no low hand qualifies.
+///
+/// # Examples
+/// ```
+/// use std::convert::TryInto;
+/// use poppy::deck::{rank_omaha_low, CardCollection};
+///
+/// let hand: CardCollection = "As2dKhKc".try_into().unwrap();
+/// let board: CardCollection = "3c5h8dJsQs".try_into().unwrap();
+/// assert!(rank_omaha_low(&hand, &board).is_some());
+///
+/// // the board offers only two low cards
+/// let board: CardCollection = "3c5h9dJsQs".try_into().unwrap();
+/// assert!(rank_omaha_low(&hand, &board).is_none());
+/// ```
+pub fn rank_omaha_low(hand: &[Card], board: &[Card]) -> Option<LowRank> {
+    omaha_hands(hand, board)
+        .map(|cards| rank_ace_to_five(&cards))
+        .filter(LowRank::is_eight_or_better)
+        .max()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::super::card::Value;
+    use super::super::card::{Suit, Value};
     use super::super::{CardCollection, Rankable};
     use super::*;
     use std::convert::TryInto;
@@ -505,4 +830,269 @@ mod tests {
         let low_rank = 1 << Value::King as u32;
         assert_eq!(Rank::TwoPair(pair_rank | low_rank), h.rank());
     }
+
+    #[test]
+    fn test_rank_omaha_uses_two_hole_cards() {
+        let hand: CardCollection = "AhAdAc2s".try_into().unwrap();
+        let board: CardCollection = "KhKd7c9d3s".try_into().unwrap();
+        // only two aces may be used, the third does not make a full house
+        let rank = rank_omaha(&hand, &board);
+        assert!(Rank::TwoPair(0) <= rank);
+        assert!(Rank::ThreeOfAKind(0) > rank);
+    }
+
+    #[test]
+    fn test_rank_omaha_uses_three_board_cards() {
+        let hand: CardCollection = "Ah9cTd2s".try_into().unwrap();
+        let board: CardCollection = "KhQhJh3h5c".try_into().unwrap();
+        // a single heart in hand does not make a flush
+        let rank = rank_omaha(&hand, &board);
+        assert!(Rank::Straight(0) <= rank);
+        assert!(Rank::Flush(0) > rank);
+    }
+
+    #[test]
+    fn test_rank_omaha_matches_rank_five() {
+        let hand: CardCollection = "9c8c2h2d".try_into().unwrap();
+        let board: CardCollection = "7c6c5cKsKd".try_into().unwrap();
+        let best: CardCollection = "9c8c7c6c5c".try_into().unwrap();
+        assert_eq!(rank_omaha(&hand, &board), best.rank_five());
+    }
+
+    #[test]
+    fn test_rank_ace_to_five() {
+        let rank = |cards: &str| -> LowRank {
+            let cards: CardCollection = cards.try_into().unwrap();
+            rank_ace_to_five(&cards)
+        };
+        // straights and flushes do not count
+        assert!(rank("5c4c3c2cAc") > rank("6d4c3c2cAc"));
+        assert!(rank("7d6c4c3c2c") > rank("7d6c5c2cAc"));
+        assert!(rank("KdQcJcTc8c") > rank("AdAc2c3c4c"));
+        assert!(rank("2d2c3h3s4c") < rank("AdAc2c3c4c"));
+        assert!(rank("2d2c2h3c4c") < rank("2s2h3h3s4c"));
+        assert_eq!(rank("5c4c3c2cAc"), rank("5d4h3s2dAs"));
+
+        assert!(rank("8c7c6c5c4c").is_eight_or_better());
+        assert!(!rank("9c4c3c2cAc").is_eight_or_better());
+        assert!(!rank("AdAc2c3c4c").is_eight_or_better());
+    }
+
+    #[test]
+    fn test_rank_omaha_low() {
+        let board: CardCollection = "2c4d7hKsKd".try_into().unwrap();
+        let hand: CardCollection = "Ac3hKcQh".try_into().unwrap();
+        let low: CardCollection = "7h4d3h2cAc".try_into().unwrap();
+        assert_eq!(rank_omaha_low(&hand, &board), Some(rank_ace_to_five(&low)));
+
+        // exactly two hole cards have to be used
+        let hand: CardCollection = "Ac9h9s9d".try_into().unwrap();
+        assert_eq!(rank_omaha_low(&hand, &board), None);
+
+        // paired hole cards cannot make a low with a paired board
+        let board: CardCollection = "2c4d4hKsKd".try_into().unwrap();
+        let hand: CardCollection = "Ac3h2d3s".try_into().unwrap();
+        assert_eq!(rank_omaha_low(&hand, &board), None);
+    }
+
+    #[test]
+    fn test_rank_short_deck_wheel() {
+        let hand: CardCollection = "As6d7c8h9s".try_into().unwrap();
+        assert_eq!(rank_short_deck(&hand), Rank::Straight(0));
+
+        let higher: CardCollection = "6d7c8h9sTs".try_into().unwrap();
+        assert!(rank_short_deck(&higher) > rank_short_deck(&hand));
+
+        let trips: CardCollection = "As6d7c8h9s9d9h".try_into().unwrap();
+        assert_eq!(rank_short_deck(&trips), Rank::Straight(0));
+
+        let flush: CardCollection = "As6s7s8s9sKs".try_into().unwrap();
+        assert_eq!(rank_short_deck(&flush), Rank::StraightFlush(0));
+    }
+
+    #[test]
+    fn test_cmp_short_deck() {
+        let flush: CardCollection = "6s8sTsQsAs".try_into().unwrap();
+        let full_house: CardCollection = "AhAdAcKsKd".try_into().unwrap();
+        let quads: CardCollection = "6h6d6c6sKd".try_into().unwrap();
+        let (flush, full_house, quads) = (
+            rank_short_deck(&flush),
+            rank_short_deck(&full_house),
+            rank_short_deck(&quads),
+        );
+
+        assert_eq!(cmp_short_deck(&flush, &full_house), Ordering::Greater);
+        assert_eq!(cmp_short_deck(&quads, &flush), Ordering::Greater);
+        assert_eq!(cmp_short_deck(&flush, &flush), Ordering::Equal);
+    }
+
+    /// Asserts that the given hands are ordered from the best to the worst hand.
+    fn assert_ordered(hands: &[&str], rank: fn(&[Card]) -> LowRank) {
+        let ranks: Vec<LowRank> = hands
+            .iter()
+            .map(|&hand| {
+                let cards: CardCollection = hand.try_into().unwrap();
+                rank(&cards)
+            })
+            .collect();
+        for (i, better) in ranks.iter().enumerate() {
+            for (j, worse) in ranks.iter().enumerate().skip(i + 1) {
+                assert!(better > worse, "{} should beat {}", hands[i], hands[j]);
+            }
+        }
+    }
+
+    /// Returns one hand for each combination of values, i. e. all hands apart from suits.
+    ///
+    /// Hands without pairs are returned twice, once as a flush and once in different suits.
+    fn all_value_combinations() -> Vec<Vec<Card>> {
+        let values = Value::values();
+        let suits = Suit::suits();
+        let mut hands = Vec::new();
+        let mut indices = [0usize; 5];
+        loop {
+            // at most four cards of each value
+            if indices[0] != indices[4] {
+                let hand: Vec<Card> = indices
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &v)| Card {
+                        value: values[v],
+                        suit: suits[i % 4],
+                    })
+                    .collect();
+                if indices.windows(2).all(|w| w[0] != w[1]) {
+                    hands.push(
+                        hand.iter()
+                            .map(|&c| Card {
+                                suit: Suit::Club,
+                                ..c
+                            })
+                            .collect(),
+                    );
+                }
+                hands.push(hand);
+            }
+
+            // next non-decreasing sequence of value indices
+            match (0..5).rev().find(|&i| indices[i] < 12) {
+                Some(i) => {
+                    let next = indices[i] + 1;
+                    for index in indices[i..].iter_mut() {
+                        *index = next;
+                    }
+                }
+                None => break,
+            }
+        }
+        hands
+    }
+
+    #[test]
+    fn test_ace_to_five_orderings() {
+        assert_ordered(
+            &[
+                "5c4d3h2sAc",
+                "6c4d3h2sAc",
+                "6c5d3h2sAc",
+                "6c5d4h2sAc",
+                "6c5d4h3sAc",
+                "6c5d4h3s2c",
+                "7c4d3h2sAc",
+                "7c6d5h4s2c",
+                "7c6d5h4s3c",
+                "8c4d3h2sAc",
+                "8c7d6h5s4c",
+                "9c4d3h2sAc",
+                "KcQdJhTs9c",
+                "AcAd2h3s4c",
+                "AcAd2h3s5c",
+                "2c2dAh3s4c",
+                "KcKdQhJsTc",
+                "2c2dAhAs3c",
+                "3c3dAhAs2c",
+                "KcKdQhQsJc",
+                "AcAdAh2s3c",
+                "KcKdKhQsJc",
+                "AcAdAh2s2c",
+                "KcKdKhQsQc",
+                "AcAdAhAs2c",
+                "KcKdKhKsQc",
+            ],
+            rank_ace_to_five,
+        );
+    }
+
+    #[test]
+    fn test_deuce_to_seven_orderings() {
+        assert_ordered(
+            &[
+                "7d5c4s3h2c",
+                "7d6c4s3h2c",
+                "7d6c5s3h2c",
+                "7d6c5s4h2c",
+                "8d5c4s3h2c",
+                "8d6c4s3h2c",
+                "8d6c5s4h2c",
+                "8d7c6s5h3c",
+                "9d5c4s3h2c",
+                "KdQcJsTh8c",
+                // the ace is high
+                "Ad5c4s3h2c",
+                "Ad6c4s3h2c",
+                "AdKcQsJh9c",
+                "2d2c5s4h3c",
+                "2d2c7s4h3c",
+                "3d3c4s5h2c",
+                "AdAcKsQhJc",
+                "3d3c2s2h4c",
+                "AdAcKsKhQc",
+                "2d2c2s3h4c",
+                "AdAcAsKhQc",
+                "6d5c4s3h2c",
+                "7d6c5s4h3c",
+                "AdKcQsJhTc",
+                "7c5c4c3c2c",
+                "Kc5c4c3c2c",
+                "Ac5c4c3c2c",
+                "AcKcQcJc9c",
+                "2d2c2s3h3c",
+                "AdAcAsKhKc",
+                "2d2c2s2h3c",
+                "AdAcAsAhKc",
+                "6c5c4c3c2c",
+                "AcKcQcJcTc",
+            ],
+            rank_deuce_to_seven,
+        );
+    }
+
+    #[test]
+    fn test_number_of_distinct_low_ranks() {
+        let hands = all_value_combinations();
+
+        // straights and flushes do not count in ace-to-five
+        let mut ranks: Vec<u32> = hands.iter().map(|h| rank_ace_to_five(h).0).collect();
+        ranks.sort_unstable();
+        ranks.dedup();
+        assert_eq!(ranks.len(), 6175);
+
+        // there are as many deuce-to-seven ranks as there are high ranks
+        let mut ranks: Vec<u32> = hands.iter().map(|h| rank_deuce_to_seven(h).0).collect();
+        ranks.sort_unstable();
+        ranks.dedup();
+        assert_eq!(ranks.len(), 7462);
+    }
+
+    #[test]
+    fn test_best_low_hand_of_seven_cards() {
+        let cards: CardCollection = "AdAc2s2h3c3d4h".try_into().unwrap();
+        let best: CardCollection = "AdAc2s3c4h".try_into().unwrap();
+        assert_eq!(cards.rank_ace_to_five_low(), rank_ace_to_five(&best));
+
+        // the flush has to be broken up
+        let cards: CardCollection = "7c5c4c3c2cKd8h".try_into().unwrap();
+        let best: CardCollection = "8h5c4c3c2c".try_into().unwrap();
+        assert_eq!(cards.rank_deuce_to_seven_low(), rank_deuce_to_seven(&best));
+    }
 }
@@ -0,0 +1,232 @@
+//! This module provides conversion of rounds played into standardized hand history formats.
+//!
+//! A round is described completely by its stream of `Action`s (see `TransparentState::actions`).
+//! Everything which cannot be recovered from the actions themselves (i.e. the names of the
+//! players) is supplied using a `TableInfo`.
+//!
+//! Currently the text format used by PokerStars is supported, which can be loaded into most
+//! tracking tools. The Open Hand History JSON format is supported if the `serde` feature is
+//! enabled.
+//!
+//! Hand histories can be imported as well. Imported hands are replayed by the engine in order
+//! to verify that they are consistent with the rules implemented by this crate.
+use crate::actions::Action;
+use crate::deck::{Card, Rank};
+use crate::replay::{self, Divergence};
+use crate::{ChipCount, RoundCheckpoint, TransparentState};
+use std::fmt;
+
+#[cfg(feature = "serde")]
+mod ohh;
+mod pokerstars;
+
+#[cfg(feature = "serde")]
+pub use ohh::{from_ohh, to_ohh};
+pub use pokerstars::{from_pokerstars, split_pokerstars, to_pokerstars};
+
+/// Meta information about the table a round was played at.
+///
+/// This is required to supplement the information contained in the `Action` stream of a round.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableInfo {
+    /// The name of the table.
+    pub table_name: String,
+    /// The names of the players, indexed by player position.
+    pub seat_names: Vec<String>,
+    /// The time the round started at, formatted as `YYYY/MM/DD HH:MM:SS`.
+    pub start_time: String,
+    /// The position of the player whose point of view the history is written from.
+    ///
+    /// If this is `None` the hole cards of all players are written.
+    pub hero: Option<usize>,
+}
+
+impl TableInfo {
+    /// Create table information for the given table name and player names.
+    ///
+    /// The start time defaults to the unix epoch and no hero is set.
+    pub fn new(table_name: impl Into<String>, seat_names: Vec<String>) -> Self {
+        Self {
+            table_name: table_name.into(),
+            seat_names,
+            start_time: String::from("1970/01/01 00:00:00"),
+            hero: None,
+        }
+    }
+
+    fn seat_name(&self, position: usize) -> Result<&str, HistoryError> {
+        self.seat_names
+            .get(position)
+            .map(String::as_str)
+            .ok_or(HistoryError::UnknownSeat(position))
+    }
+}
+
+/// A hand which was imported from a hand history.
+///
+/// It is guaranteed to be consistent with the rules of the engine.
+#[derive(Debug, Clone)]
+pub struct ImportedHand {
+    /// Information about the table and the players which is not part of the actions.
+    pub info: TableInfo,
+    /// The actions of the round in the order the engine emits them.
+    ///
+    /// Hole cards which are not revealed in the hand history are substituted with arbitrary
+    /// cards which are not in play otherwise. The same is true for board cards which were not dealt.
+    pub actions: Vec<Action>,
+    /// The state after the round was replayed.
+    pub state: TransparentState,
+}
+
+impl ImportedHand {
+    /// Validates the given actions by replaying them.
+    fn new(info: TableInfo, actions: Vec<Action>) -> Result<Self, HistoryError> {
+        let state = replay::replay(&actions).map_err(HistoryError::Inconsistent)?;
+
+        Ok(Self {
+            info,
+            actions,
+            state,
+        })
+    }
+
+    /// Assemble the complete actions of an imported round and validate them.
+    ///
+    /// `start` is expected to be an `Action::StartRound`, `actions` are all the actions
+    /// following the deal of the hole cards except for the final `Action::Win`.
+    /// Hole cards which are not known are substituted.
+    fn assemble(
+        info: TableInfo,
+        start: Action,
+        hands: &[Option<Vec<Card>>],
+        mut actions: Vec<Action>,
+        wins: Vec<(usize, ChipCount)>,
+    ) -> Result<Self, HistoryError> {
+        let (num_players, dealer_position, variant) = match &start {
+            Action::StartRound {
+                player_stacks,
+                dealer_position,
+                variant,
+                ..
+            } => (player_stacks.len(), *dealer_position, *variant),
+            _ => return Err(HistoryError::MissingStartRound),
+        };
+        if num_players < 2 {
+            return Err(HistoryError::Unsupported(String::from(
+                "less than two players",
+            )));
+        } else if num_players == 2 {
+            return Err(HistoryError::Unsupported(String::from(
+                "heads-up play with the button posting the small blind",
+            )));
+        }
+
+        let folded: Vec<bool> = (0..num_players)
+            .map(|pos| actions.contains(&Action::Fold(pos)))
+            .collect();
+        if folded.iter().filter(|&&folded| !folded).count() > 1 {
+            if let Some(pos) = (0..num_players).find(|&pos| !folded[pos] && hands[pos].is_none()) {
+                return Err(HistoryError::Unsupported(format!(
+                    "hole cards of {} are not revealed at showdown",
+                    info.seat_name(pos)?
+                )));
+            }
+        }
+
+        let mut known_cards: Vec<Card> = hands.iter().flatten().flatten().copied().collect();
+        for action in actions.iter() {
+            match action {
+                Action::DealFlop(cards) => known_cards.extend_from_slice(cards),
+                Action::DealTurn(card) | Action::DealRiver(card) => known_cards.push(*card),
+                _ => {}
+            }
+        }
+        let mut unused_cards = replay::unused_cards(variant, &known_cards);
+
+        let mut all_actions = vec![start];
+        for i in 1..=num_players {
+            let pos = (dealer_position + i) % num_players;
+            let cards = match &hands[pos] {
+                Some(cards) => cards.clone(),
+                None => (&mut unused_cards).take(variant.hole_cards()).collect(),
+            };
+            all_actions.push(Action::DealHand(pos, cards));
+        }
+        all_actions.append(&mut actions);
+        if !wins.is_empty() {
+            all_actions.push(Action::Win(wins));
+        }
+
+        Self::new(info, all_actions)
+    }
+
+    /// Reconstruct the checkpoint at the start of this round.
+    ///
+    /// This can be used to replay the round with different players, see `Table::replay_one_round`.
+    pub fn checkpoint(&self) -> RoundCheckpoint {
+        replay::initial_checkpoint(&self.actions).expect("Imported actions start a round")
+    }
+}
+
+/// Errors which can occur while converting hand histories.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HistoryError {
+    /// The action stream does not start with an `Action::StartRound`.
+    MissingStartRound,
+    /// There is no name available for the player at the given position.
+    UnknownSeat(usize),
+    /// The given action is not valid at this point of the round.
+    UnexpectedAction(Action),
+    /// The given line (starting at 1) of a hand history could not be parsed.
+    InvalidLine {
+        /// The number of the line.
+        line: usize,
+        /// The content of the line.
+        content: String,
+    },
+    /// The hand history could not be read, i. e. it is not valid JSON or contains invalid values.
+    Malformed(String),
+    /// The hand history uses a feature which is not supported by this crate.
+    Unsupported(String),
+    /// Replaying the hand history did not produce the recorded results.
+    ///
+    /// This usually indicates that the hand was played using different rules.
+    Inconsistent(Divergence),
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HistoryError::MissingStartRound => {
+                write!(f, "The actions do not start with a `StartRound` action")
+            }
+            HistoryError::UnknownSeat(pos) => write!(f, "No name known for position {}", pos),
+            HistoryError::UnexpectedAction(action) => write!(f, "Unexpected action {:?}", action),
+            HistoryError::InvalidLine { line, content } => {
+                write!(f, "Could not parse line {}: {}", line, content)
+            }
+            HistoryError::Malformed(reason) => write!(f, "Malformed hand history: {}", reason),
+            HistoryError::Unsupported(feature) => write!(f, "Unsupported feature: {}", feature),
+            HistoryError::Inconsistent(divergence) => {
+                write!(f, "Inconsistent hand history: {}", divergence)
+            }
+        }
+    }
+}
+
+impl std::error::Error for HistoryError {}
+
+/// A short human readable description of the given rank, i. e. "a full house".
+fn describe_rank(rank: &Rank) -> &'static str {
+    match rank {
+        Rank::HighCard(_) => "high card",
+        Rank::OnePair(_) => "a pair",
+        Rank::TwoPair(_) => "two pair",
+        Rank::ThreeOfAKind(_) => "three of a kind",
+        Rank::Straight(_) => "a straight",
+        Rank::Flush(_) => "a flush",
+        Rank::FullHouse(_) => "a full house",
+        Rank::FourOfAKind(_) => "four of a kind",
+        Rank::StraightFlush(_) => "a straight flush",
+    }
+}
@@ -0,0 +1,930 @@
+//! Conversion of rounds from and into the Open Hand History JSON format.
+//!
+//! See <https://hh-specs.handhistory.org> for the specification.
+use super::{HistoryError, ImportedHand, TableInfo};
+use crate::actions::Action;
+use crate::deck::{Card, CardCollection};
+use crate::{BettingStructure, ChipCount, GameVariant};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// The version of the specification which is written.
+const SPEC_VERSION: &str = "1.4.6";
+/// The currency written for exported rounds. Chips do not have any associated value.
+const CHIPS: &str = "CHIPS";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Document {
+    ohh: HandHistory,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HandHistory {
+    spec_version: String,
+    #[serde(default)]
+    site_name: String,
+    #[serde(default)]
+    network_name: String,
+    #[serde(default)]
+    internal_version: String,
+    game_number: String,
+    start_date_utc: String,
+    #[serde(default)]
+    table_name: String,
+    game_type: String,
+    bet_limit: BetLimit,
+    table_size: usize,
+    #[serde(default)]
+    currency: String,
+    dealer_seat: usize,
+    small_blind_amount: f64,
+    big_blind_amount: f64,
+    #[serde(default)]
+    ante_amount: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hero_player_id: Option<usize>,
+    players: Vec<HandPlayer>,
+    rounds: Vec<HandRound>,
+    #[serde(default)]
+    pots: Vec<HandPot>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BetLimit {
+    bet_type: String,
+    #[serde(default)]
+    bet_cap: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HandPlayer {
+    id: usize,
+    seat: usize,
+    name: String,
+    starting_stack: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HandRound {
+    id: usize,
+    street: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cards: Option<Vec<String>>,
+    actions: Vec<HandAction>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HandAction {
+    action_number: usize,
+    player_id: usize,
+    action: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    amount: Option<f64>,
+    #[serde(default)]
+    is_allin: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cards: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HandPot {
+    number: usize,
+    amount: f64,
+    #[serde(default)]
+    rake: f64,
+    player_wins: Vec<PlayerWin>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PlayerWin {
+    player_id: usize,
+    win_amount: f64,
+    #[serde(default)]
+    contributed_rake: f64,
+}
+
+/// Convert the actions of a single round into a hand history in the Open Hand History format.
+///
+/// The same requirements as for `to_pokerstars` apply to the given `actions`.
+/// Player ids correspond to player positions, seat numbers start at 1.
+///
+/// Amounts are written in chips. The amount of every betting action is the number of chips
+/// added to the pot by this action, i. e. not the total of a raise.
+/// Chips which are returned because a bet was not called are not reported as winnings.
+pub fn to_ohh(actions: &[Action], info: &TableInfo) -> Result<String, HistoryError> {
+    let (
+        id,
+        small_blind,
+        big_blind,
+        dealer_position,
+        player_stacks,
+        betting_structure,
+        variant,
+        ante,
+    ) = match actions.first() {
+        Some(Action::StartRound {
+            bomb_pot: Some(_), ..
+        }) => return Err(HistoryError::Unsupported(String::from("bomb pots"))),
+        Some(Action::StartRound {
+            id,
+            small_blind,
+            big_blind,
+            dealer_position,
+            player_stacks,
+            betting_structure,
+            variant,
+            ante,
+            ..
+        }) => (
+            *id,
+            *small_blind,
+            *big_blind,
+            *dealer_position,
+            player_stacks,
+            *betting_structure,
+            *variant,
+            *ante,
+        ),
+        _ => return Err(HistoryError::MissingStartRound),
+    };
+    let game_type = game_type(variant)
+        .ok_or_else(|| HistoryError::Unsupported(format!("game variant {:?}", variant)))?;
+
+    let num_players = player_stacks.len();
+    let mut writer = Writer {
+        info,
+        rounds: vec![HandRound {
+            id: 0,
+            street: String::from("Preflop"),
+            cards: None,
+            actions: Vec::new(),
+        }],
+        action_number: 0,
+        hands: vec![None; num_players],
+        folded: vec![false; num_players],
+        street_bets: vec![0; num_players],
+        total_bets: vec![0; num_players],
+        forced_bets: 0,
+        pots: Vec::new(),
+    };
+
+    let mut players = Vec::new();
+    for action in actions.iter() {
+        if let Action::DealHand(pos, _) = action {
+            players.push(HandPlayer {
+                id: *pos,
+                seat: pos + 1,
+                name: info.seat_name(*pos)?.to_string(),
+                starting_stack: f64::from(player_stacks[*pos]),
+            });
+        }
+    }
+    players.sort_by_key(|player| player.seat);
+
+    for action in actions[1..].iter() {
+        writer.write_action(action)?;
+        if let Action::Win(_) = action {
+            break;
+        }
+    }
+
+    let history = HandHistory {
+        spec_version: String::from(SPEC_VERSION),
+        site_name: String::from("poppy"),
+        network_name: String::from("poppy"),
+        internal_version: String::from(env!("CARGO_PKG_VERSION")),
+        game_number: id.to_string(),
+        start_date_utc: to_iso_date(&info.start_time),
+        table_name: info.table_name.clone(),
+        game_type: String::from(game_type),
+        bet_limit: BetLimit {
+            bet_type: String::from(bet_type(betting_structure)),
+            bet_cap: 0.0,
+        },
+        table_size: num_players,
+        currency: String::from(CHIPS),
+        dealer_seat: dealer_position + 1,
+        small_blind_amount: f64::from(small_blind),
+        big_blind_amount: f64::from(big_blind),
+        ante_amount: f64::from(ante),
+        hero_player_id: info.hero,
+        players,
+        rounds: writer.rounds,
+        pots: writer.pots,
+    };
+
+    serde_json::to_string_pretty(&Document { ohh: history })
+        .map_err(|e| HistoryError::Malformed(e.to_string()))
+}
+
+/// Helper to keep track of everything which is required to write a hand history.
+struct Writer<'a> {
+    info: &'a TableInfo,
+    rounds: Vec<HandRound>,
+    action_number: usize,
+    hands: Vec<Option<Vec<Card>>>,
+    folded: Vec<bool>,
+    street_bets: Vec<ChipCount>,
+    total_bets: Vec<ChipCount>,
+    forced_bets: usize,
+    pots: Vec<HandPot>,
+}
+
+impl<'a> Writer<'a> {
+    fn write_action(&mut self, action: &Action) -> Result<(), HistoryError> {
+        match action {
+            Action::StartRound { .. }
+            | Action::DealUp(..)
+            | Action::DealDown(..)
+            | Action::BringIn(..) => return Err(HistoryError::UnexpectedAction(action.clone())),
+            Action::Run(..) => {
+                return Err(HistoryError::Unsupported(String::from(
+                    "running the board multiple times",
+                )))
+            }
+            Action::SecondBoard(_) => {
+                return Err(HistoryError::Unsupported(String::from("double boards")))
+            }
+            Action::MissedBlinds(..) => {
+                return Err(HistoryError::Unsupported(String::from("missed blinds")))
+            }
+            Action::IncreaseBlind(_)
+            | Action::SitOut(_)
+            | Action::Return(_)
+            | Action::Timeout(_) => {}
+            Action::DealHand(pos, cards) => {
+                self.hands[*pos] = Some(cards.clone());
+                if self.info.hero.map(|hero| hero == *pos).unwrap_or(true) {
+                    self.push_action(*pos, "Dealt Cards", None, false, Some(cards));
+                }
+            }
+            Action::Ante(pos, c) => {
+                self.push_action(*pos, "Post Ante", Some(*c), false, None);
+                // antes do not count as bets of the street
+                self.total_bets[*pos] += c;
+            }
+            Action::Blind(pos, c) => self.write_forced_bet(*pos, *c, false),
+            Action::Straddle(pos, c) => {
+                self.push_action(*pos, "Straddle", Some(*c), false, None);
+                self.place_chips(*pos, *c);
+            }
+            Action::AllIn(pos, c) if self.forced_bets < 2 => self.write_forced_bet(*pos, *c, true),
+            Action::DealFlop(cards) => self.start_street("Flop", cards),
+            Action::DealTurn(card) => self.start_street("Turn", &[*card]),
+            Action::DealRiver(card) => self.start_street("River", &[*card]),
+            Action::Check(pos) => self.push_action(*pos, "Check", None, false, None),
+            Action::Fold(pos) => {
+                self.folded[*pos] = true;
+                self.push_action(*pos, "Fold", None, false, None);
+            }
+            Action::Call(pos, c)
+            | Action::Raise(pos, c)
+            | Action::AllIn(pos, c)
+            | Action::Bet(pos, c) => {
+                let level = self.street_bets.iter().copied().max().unwrap_or(0);
+                let name = match action {
+                    _ if self.street_bets[*pos] + c <= level => "Call",
+                    Action::Bet(..) => "Bet",
+                    Action::AllIn(..) if level == 0 => "Bet",
+                    _ => "Raise",
+                };
+                let all_in = matches!(action, Action::AllIn(..));
+                self.push_action(*pos, name, Some(*c), all_in, None);
+                self.place_chips(*pos, *c);
+            }
+            Action::Win(wins) => self.write_showdown(wins),
+        }
+
+        Ok(())
+    }
+
+    fn push_action(
+        &mut self,
+        pos: usize,
+        name: &str,
+        amount: Option<ChipCount>,
+        is_allin: bool,
+        cards: Option<&[Card]>,
+    ) {
+        self.action_number += 1;
+        let action = HandAction {
+            action_number: self.action_number,
+            player_id: pos,
+            action: String::from(name),
+            amount: amount.map(f64::from),
+            is_allin,
+            cards: cards.map(format_cards),
+        };
+        self.rounds.last_mut().unwrap().actions.push(action);
+    }
+
+    fn write_forced_bet(&mut self, pos: usize, c: ChipCount, all_in: bool) {
+        let name = if self.forced_bets == 0 {
+            "Post SB"
+        } else {
+            "Post BB"
+        };
+        self.push_action(pos, name, Some(c), all_in, None);
+        self.forced_bets += 1;
+        self.place_chips(pos, c);
+    }
+
+    fn start_street(&mut self, street: &str, cards: &[Card]) {
+        for bet in self.street_bets.iter_mut() {
+            *bet = 0;
+        }
+        self.rounds.push(HandRound {
+            id: self.rounds.len(),
+            street: String::from(street),
+            cards: Some(format_cards(cards)),
+            actions: Vec::new(),
+        });
+    }
+
+    fn write_showdown(&mut self, wins: &[(usize, ChipCount)]) {
+        let remaining: Vec<usize> = (0..self.folded.len())
+            .filter(|&pos| !self.folded[pos] && self.hands[pos].is_some())
+            .collect();
+        if remaining.len() > 1 {
+            self.rounds.push(HandRound {
+                id: self.rounds.len(),
+                street: String::from("Showdown"),
+                cards: None,
+                actions: Vec::new(),
+            });
+            for pos in remaining {
+                let cards = self.hands[pos].clone().unwrap();
+                self.push_action(pos, "Shows Cards", None, false, Some(&cards));
+            }
+        }
+
+        // the part of a bet which is not called by anyone is "won" back in the game
+        let mut uncalled = uncalled_bet(&self.total_bets);
+        let mut player_wins = Vec::new();
+        for &(pos, amount) in wins.iter() {
+            let amount = match uncalled.take() {
+                Some((top, excess)) if top == pos => amount.saturating_sub(excess),
+                other => {
+                    uncalled = other;
+                    amount
+                }
+            };
+            if amount > 0 {
+                player_wins.push(PlayerWin {
+                    player_id: pos,
+                    win_amount: f64::from(amount),
+                    contributed_rake: 0.0,
+                });
+            }
+        }
+        self.pots.push(HandPot {
+            number: 0,
+            amount: player_wins.iter().map(|win| win.win_amount).sum(),
+            rake: 0.0,
+            player_wins,
+        });
+    }
+
+    fn place_chips(&mut self, pos: usize, c: ChipCount) {
+        self.street_bets[pos] += c;
+        self.total_bets[pos] += c;
+    }
+}
+
+/// Parse a single hand from a hand history in the Open Hand History format.
+///
+/// Only Hold'em hands are supported. Limit games are expected to be capped at four bets per
+/// street. Amounts of real money games, i. e. games with a three letter currency code, are
+/// converted into cents. Otherwise amounts are taken as chips.
+/// The amount of every betting action is expected to be the number of chips added to the pot
+/// by this action.
+///
+/// As with `from_pokerstars` the hand is replayed in order to verify it and the rake of each pot
+/// is attributed to the player winning it. Raked pots won by several players and heads-up hands
+/// are not supported either.
+pub fn from_ohh(json: &str) -> Result<ImportedHand, HistoryError> {
+    let document: Document =
+        serde_json::from_str(json).map_err(|e| HistoryError::Malformed(e.to_string()))?;
+    let history = document.ohh;
+
+    let betting_structure = BETTING_STRUCTURES
+        .iter()
+        .copied()
+        .find(|&structure| bet_type(structure) == history.bet_limit.bet_type);
+    let variant = VARIANTS
+        .iter()
+        .copied()
+        .find(|&variant| game_type(variant) == Some(history.game_type.as_str()));
+    let (variant, betting_structure) = match (variant, betting_structure) {
+        (Some(variant), Some(betting_structure)) => (variant, betting_structure),
+        _ => {
+            return Err(HistoryError::Unsupported(format!(
+                "game `{} {}`",
+                history.game_type, history.bet_limit.bet_type
+            )))
+        }
+    };
+    let scale = if history.currency.len() == 3
+        && history.currency.chars().all(|c| c.is_ascii_uppercase())
+    {
+        100
+    } else {
+        1
+    };
+    let chips = |amount: f64| to_chips(amount, scale);
+
+    let mut players = history.players;
+    players.sort_by_key(|player| player.seat);
+    let position = |player_id: usize| {
+        players
+            .iter()
+            .position(|player| player.id == player_id)
+            .ok_or_else(|| HistoryError::Malformed(format!("unknown player id {}", player_id)))
+    };
+    let num_players = players.len();
+
+    let dealer_seat = history.dealer_seat;
+    let dealer_position = players
+        .iter()
+        .position(|player| player.seat == dealer_seat)
+        .ok_or_else(|| HistoryError::Unsupported(String::from("dead button")))?;
+
+    let mut hands = vec![None; num_players];
+    let mut actions = Vec::new();
+    let mut street_bets = vec![0; num_players];
+    let mut total_bets = vec![0; num_players];
+    for round in history.rounds.iter() {
+        match round.street.as_str() {
+            "Preflop" | "Showdown" => {}
+            "Flop" | "Turn" | "River" => {
+                let cards = parse_cards(round.cards.as_deref().unwrap_or_default())?;
+                let deal = match (round.street.as_str(), &cards[..]) {
+                    ("Flop", &[c1, c2, c3]) => Action::DealFlop([c1, c2, c3]),
+                    ("Turn", &[card]) => Action::DealTurn(card),
+                    ("River", &[card]) => Action::DealRiver(card),
+                    _ => {
+                        return Err(HistoryError::Malformed(format!(
+                            "invalid number of cards on the {}",
+                            round.street
+                        )))
+                    }
+                };
+                actions.push(deal);
+                for bet in street_bets.iter_mut() {
+                    *bet = 0;
+                }
+            }
+            street => return Err(HistoryError::Unsupported(format!("street `{}`", street))),
+        }
+
+        for action in round.actions.iter() {
+            let pos = position(action.player_id)?;
+            let amount = || {
+                action
+                    .amount
+                    .ok_or_else(|| {
+                        HistoryError::Malformed(format!("missing amount of {}", action.action))
+                    })
+                    .and_then(chips)
+            };
+
+            let parsed = match action.action.as_str() {
+                "Dealt Cards" | "Shows Cards" | "Mucks Cards" => {
+                    let cards = parse_cards(action.cards.as_deref().unwrap_or_default())?;
+                    if cards.len() != variant.hole_cards() {
+                        return Err(HistoryError::Malformed(String::from(
+                            "invalid number of hole cards",
+                        )));
+                    }
+                    hands[pos] = Some(cards.to_vec());
+                    continue;
+                }
+                "Post Ante" => {
+                    // antes do not count as bets of the street
+                    let ante = amount()?;
+                    total_bets[pos] += ante;
+                    actions.push(Action::Ante(pos, ante));
+                    continue;
+                }
+                "Post SB" | "Post BB" => Action::Blind(pos, amount()?),
+                "Straddle" => Action::Straddle(pos, amount()?),
+                "Fold" => Action::Fold(pos),
+                "Check" => Action::Check(pos),
+                "Call" => Action::Call(pos, amount()?),
+                "Bet" => Action::Bet(pos, amount()?),
+                "Raise" => Action::Raise(pos, amount()?),
+                other => return Err(HistoryError::Unsupported(format!("action `{}`", other))),
+            };
+            let placed = match parsed {
+                Action::Blind(_, c)
+                | Action::Straddle(_, c)
+                | Action::Call(_, c)
+                | Action::Bet(_, c)
+                | Action::Raise(_, c) => c,
+                _ => 0,
+            };
+            street_bets[pos] += placed;
+            total_bets[pos] += placed;
+            actions.push(if action.is_allin {
+                Action::AllIn(pos, placed)
+            } else {
+                parsed
+            });
+        }
+    }
+
+    let mut wins: Vec<(usize, ChipCount)> = Vec::new();
+    let mut add_win = |pos: usize, amount: ChipCount| match wins.iter_mut().find(|(p, _)| *p == pos)
+    {
+        Some((_, total)) => *total += amount,
+        None => wins.push((pos, amount)),
+    };
+    for pot in history.pots.iter() {
+        // the engine does not take rake, it can only be attributed to a single winner
+        let rake = chips(pot.rake)?;
+        if rake > 0 && pot.player_wins.len() > 1 {
+            return Err(HistoryError::Unsupported(String::from(
+                "rake of pots won by several players",
+            )));
+        }
+        for win in pot.player_wins.iter() {
+            add_win(position(win.player_id)?, chips(win.win_amount)? + rake);
+        }
+    }
+    if let Some((top, excess)) = uncalled_bet(&total_bets) {
+        add_win(top, excess);
+    }
+
+    let game_number = history.game_number;
+    let id = game_number
+        .parse()
+        .map_err(|_| HistoryError::Malformed(format!("game number {}", game_number)))?;
+    let start = Action::StartRound {
+        id,
+        big_blind: chips(history.big_blind_amount)?,
+        small_blind: chips(history.small_blind_amount)?,
+        dealer_position,
+        player_stacks: players
+            .iter()
+            .map(|player| chips(player.starting_stack))
+            .collect::<Result<_, _>>()?,
+        betting_structure,
+        variant,
+        ante: chips(history.ante_amount)?,
+        bomb_pot: None,
+    };
+    let info = TableInfo {
+        table_name: history.table_name,
+        start_time: from_iso_date(&history.start_date_utc),
+        hero: history.hero_player_id.map(position).transpose()?,
+        seat_names: players.into_iter().map(|player| player.name).collect(),
+    };
+
+    ImportedHand::assemble(info, start, &hands, actions, wins)
+}
+
+/// The game variants which can be imported.
+const VARIANTS: &[GameVariant] = &[
+    GameVariant::Holdem,
+    GameVariant::Omaha,
+    GameVariant::OmahaHiLo,
+];
+
+/// The game type of the given variant, `None` if the format does not describe the variant.
+fn game_type(variant: GameVariant) -> Option<&'static str> {
+    match variant {
+        GameVariant::Holdem => Some("Holdem"),
+        GameVariant::Omaha => Some("Omaha"),
+        GameVariant::OmahaHiLo => Some("OmahaHiLo"),
+        GameVariant::ShortDeck | GameVariant::SevenCardStud => None,
+    }
+}
+
+/// The betting structures which can be imported.
+///
+/// Limit games are capped at four bets per street.
+const BETTING_STRUCTURES: &[BettingStructure] = &[
+    BettingStructure::NoLimit,
+    BettingStructure::PotLimit,
+    BettingStructure::FixedLimit { raise_cap: 4 },
+];
+
+fn bet_type(betting_structure: BettingStructure) -> &'static str {
+    match betting_structure {
+        BettingStructure::NoLimit => "NL",
+        BettingStructure::PotLimit => "PL",
+        BettingStructure::FixedLimit { .. } => "FL",
+    }
+}
+
+/// Returns the position of the player with the highest total bet and the amount which
+/// exceeds the bets of all other players.
+fn uncalled_bet(total_bets: &[ChipCount]) -> Option<(usize, ChipCount)> {
+    let top = (0..total_bets.len()).max_by_key(|&pos| total_bets[pos])?;
+    let second = (0..total_bets.len())
+        .filter(|&pos| pos != top)
+        .map(|pos| total_bets[pos])
+        .max()
+        .unwrap_or(0);
+    if total_bets[top] > second {
+        Some((top, total_bets[top] - second))
+    } else {
+        None
+    }
+}
+
+fn to_chips(amount: f64, scale: ChipCount) -> Result<ChipCount, HistoryError> {
+    let scaled = amount * f64::from(scale);
+    let chips = scaled.round();
+    if chips < 0.0 || chips > f64::from(ChipCount::MAX) || (scaled - chips).abs() > 1e-6 {
+        return Err(HistoryError::Malformed(format!("amount {}", amount)));
+    }
+    Ok(chips as ChipCount)
+}
+
+/// Convert `YYYY/MM/DD HH:MM:SS` into `YYYY-MM-DDTHH:MM:SSZ`.
+fn to_iso_date(time: &str) -> String {
+    format!("{}Z", time.replace('/', "-").replacen(' ', "T", 1))
+}
+
+/// Convert an ISO 8601 date into `YYYY/MM/DD HH:MM:SS`, ignoring any time zone information.
+fn from_iso_date(date: &str) -> String {
+    date.chars()
+        .take(19)
+        .map(|c| match c {
+            '-' => '/',
+            'T' => ' ',
+            c => c,
+        })
+        .collect()
+}
+
+fn format_cards(cards: &[Card]) -> Vec<String> {
+    cards.iter().map(Card::to_string).collect()
+}
+
+fn parse_cards(cards: &[String]) -> Result<Vec<Card>, HistoryError> {
+    CardCollection::try_from(cards.concat().as_str())
+        .map(|cards| cards.to_vec())
+        .map_err(HistoryError::Malformed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::PlayerAction;
+    use crate::mock::MockPlayer;
+    use crate::{BlindPolicy, Straddle, Table};
+    use std::convert::TryInto;
+
+    fn table_info() -> TableInfo {
+        TableInfo::new(
+            "Poppy",
+            vec![
+                String::from("Alice"),
+                String::from("Bob"),
+                String::from("Carol"),
+                String::from("Dave"),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_round_trip() {
+        // see `table::tests::test_play_showdown_and_one_player_wins`
+        let deck: CardCollection = "Jc9dTcQd2s2c8cKsQsTdTh7sAd".try_into().unwrap();
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Raise(10), PlayerAction::Fold]), // dealer
+            MockPlayer::new(vec![
+                PlayerAction::Blind(1),
+                PlayerAction::Call(9),
+                PlayerAction::Check,
+                PlayerAction::Call(2),
+                PlayerAction::Bet(2),
+                PlayerAction::Bet(2),
+                PlayerAction::AllIn(86),
+            ]), // small
+            MockPlayer::new(vec![
+                PlayerAction::Blind(2),
+                PlayerAction::Call(8),
+                PlayerAction::Bet(2),
+                PlayerAction::Call(2),
+                PlayerAction::Raise(10),
+                PlayerAction::AllIn(76),
+            ]), // big
+            MockPlayer::new(vec![PlayerAction::Fold]),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+        let actions: Vec<Action> = table.play_one_round(deck).unwrap().collect();
+
+        let json = to_ohh(&actions, &table_info()).unwrap();
+        let hand = from_ohh(&json).unwrap();
+        assert_eq!(hand.actions, actions);
+        assert_eq!(hand.info, table_info());
+    }
+
+    #[test]
+    fn test_straddle_round_trip() {
+        let mut straddler = MockPlayer::new(vec![PlayerAction::Check]);
+        straddler.straddles = true;
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Fold]), // dealer
+            MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Fold]), // small
+            MockPlayer::new(vec![PlayerAction::Blind(2), PlayerAction::Fold]), // big
+            straddler,
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease)
+            .with_straddle(Straddle::UnderTheGun);
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+        assert_eq!(actions[7], Action::Straddle(3, 4));
+
+        let json = to_ohh(&actions, &table_info()).unwrap();
+        let hand = from_ohh(&json).unwrap();
+        assert_eq!(hand.actions, actions);
+    }
+
+    #[test]
+    fn test_export_uncalled_bet() {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Raise(10)]), // dealer
+            MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Fold]), // small
+            MockPlayer::new(vec![PlayerAction::Blind(2), PlayerAction::Fold]), // big
+            MockPlayer::new(vec![PlayerAction::Fold]),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+
+        let mut info = table_info();
+        info.hero = Some(0);
+        let json = to_ohh(&actions, &info).unwrap();
+        let document: Document = serde_json::from_str(&json).unwrap();
+        let history = document.ohh;
+
+        assert_eq!(history.dealer_seat, 1);
+        assert_eq!(history.hero_player_id, Some(0));
+        assert_eq!(history.rounds.len(), 1);
+        let names: Vec<&str> = history.rounds[0]
+            .actions
+            .iter()
+            .map(|action| action.action.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "Dealt Cards",
+                "Post SB",
+                "Post BB",
+                "Fold",
+                "Raise",
+                "Fold",
+                "Fold"
+            ]
+        );
+        assert_eq!(history.pots.len(), 1);
+        assert_eq!(history.pots[0].amount, 5.0);
+        assert_eq!(history.pots[0].player_wins[0].player_id, 0);
+
+        // the hidden hole cards are substituted
+        let hand = from_ohh(&json).unwrap();
+        assert_eq!(hand.actions[0], actions[0]);
+        assert_eq!(hand.actions[4..], actions[4..]);
+    }
+
+    const SAMPLE: &str = r#"{
+  "ohh": {
+    "spec_version": "1.4.6",
+    "site_name": "Example",
+    "game_number": "42",
+    "start_date_utc": "2021-03-04T18:30:00Z",
+    "table_name": "Example I",
+    "game_type": "Holdem",
+    "bet_limit": { "bet_type": "NL", "bet_cap": 0 },
+    "table_size": 6,
+    "currency": "USD",
+    "dealer_seat": 5,
+    "small_blind_amount": 0.05,
+    "big_blind_amount": 0.1,
+    "ante_amount": 0,
+    "hero_player_id": 7,
+    "players": [
+      { "id": 3, "seat": 5, "name": "button", "starting_stack": 10 },
+      { "id": 7, "seat": 1, "name": "hero", "starting_stack": 5.5 },
+      { "id": 1, "seat": 3, "name": "blind", "starting_stack": 12.25 }
+    ],
+    "rounds": [
+      {
+        "id": 0,
+        "street": "Preflop",
+        "actions": [
+          { "action_number": 1, "player_id": 7, "action": "Dealt Cards", "cards": ["Ah", "Ad"] },
+          { "action_number": 2, "player_id": 7, "action": "Post SB", "amount": 0.05 },
+          { "action_number": 3, "player_id": 1, "action": "Post BB", "amount": 0.1 },
+          { "action_number": 4, "player_id": 3, "action": "Raise", "amount": 0.3 },
+          { "action_number": 5, "player_id": 7, "action": "Raise", "amount": 0.95 },
+          { "action_number": 6, "player_id": 1, "action": "Fold" },
+          { "action_number": 7, "player_id": 3, "action": "Fold" }
+        ]
+      }
+    ],
+    "pots": [
+      {
+        "number": 0,
+        "amount": 0.7,
+        "rake": 0,
+        "player_wins": [{ "player_id": 7, "win_amount": 0.7, "contributed_rake": 0 }]
+      }
+    ]
+  }
+}"#;
+
+    #[test]
+    fn test_import_sample() {
+        let hand = from_ohh(SAMPLE).unwrap();
+
+        assert_eq!(hand.info.seat_names, ["hero", "blind", "button"]);
+        assert_eq!(hand.info.hero, Some(0));
+        assert_eq!(hand.info.start_time, "2021/03/04 18:30:00");
+        assert_eq!(
+            hand.actions[0],
+            Action::StartRound {
+                id: 42,
+                big_blind: 10,
+                small_blind: 5,
+                dealer_position: 2,
+                player_stacks: vec![550, 1225, 1000],
+                betting_structure: BettingStructure::NoLimit,
+                variant: GameVariant::Holdem,
+                ante: 0,
+                bomb_pot: None,
+            }
+        );
+        // the uncalled part of the re-raise is returned
+        assert_eq!(hand.actions.last(), Some(&Action::Win(vec![(0, 140)])));
+        assert_eq!(hand.state.player_stacks, [590, 1215, 970]);
+    }
+
+    #[test]
+    fn test_import_unsupported() {
+        let json = SAMPLE.replace("\"Holdem\"", "\"Badugi\"");
+        assert!(matches!(from_ohh(&json), Err(HistoryError::Unsupported(_))));
+
+        let json = SAMPLE.replace("\"Fold\"", "\"Post Dead\"");
+        assert!(matches!(from_ohh(&json), Err(HistoryError::Unsupported(_))));
+
+        // heads-up, the button posts the small blind
+        let json = SAMPLE
+            .replace(
+                ",\n      { \"id\": 1, \"seat\": 3, \"name\": \"blind\", \"starting_stack\": 12.25 }",
+                "",
+            )
+            .replace("\"player_id\": 1,", "\"player_id\": 3,");
+        assert!(matches!(from_ohh(&json), Err(HistoryError::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_import_raked_pot() {
+        let json = SAMPLE
+            .replace("\"rake\": 0,", "\"rake\": 0.1,")
+            .replace("\"win_amount\": 0.7", "\"win_amount\": 0.6");
+        let hand = from_ohh(&json).unwrap();
+        assert_eq!(hand.actions.last(), Some(&Action::Win(vec![(0, 140)])));
+
+        let json = json.replace(
+            "[{ \"player_id\": 7, \"win_amount\": 0.6, \"contributed_rake\": 0 }]",
+            "[\n          { \"player_id\": 7, \"win_amount\": 0.3, \"contributed_rake\": 0 },\n          { \"player_id\": 3, \"win_amount\": 0.3, \"contributed_rake\": 0 }\n        ]",
+        );
+        assert_eq!(
+            from_ohh(&json).unwrap_err(),
+            HistoryError::Unsupported(String::from("rake of pots won by several players"))
+        );
+    }
+
+    #[test]
+    fn test_import_malformed() {
+        assert!(matches!(
+            from_ohh("{ \"ohh\": {} }"),
+            Err(HistoryError::Malformed(_))
+        ));
+
+        let json = SAMPLE.replace("0.95", "0.955");
+        assert_eq!(
+            from_ohh(&json).unwrap_err(),
+            HistoryError::Malformed(String::from("amount 0.955"))
+        );
+    }
+
+    #[test]
+    fn test_import_inconsistent() {
+        let json = SAMPLE.replace("\"win_amount\": 0.7", "\"win_amount\": 0.8");
+        assert!(matches!(
+            from_ohh(&json),
+            Err(HistoryError::Inconsistent(_))
+        ));
+    }
+}
@@ -0,0 +1,1617 @@
+//! Conversion of rounds from and into the text based hand history format used by PokerStars.
+use super::{describe_rank, HistoryError, ImportedHand, TableInfo};
+use crate::actions::Action;
+use crate::deck::{Card, CardCollection};
+use crate::{BettingStructure, ChipCount, GameVariant};
+use std::convert::TryFrom;
+
+/// The streets of a round. Used to describe at which point players folded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Street {
+    PreFlop,
+    Flop,
+    Turn,
+    River,
+}
+
+impl Street {
+    fn name(self) -> &'static str {
+        match self {
+            Street::PreFlop => "Pre-Flop",
+            Street::Flop => "Flop",
+            Street::Turn => "Turn",
+            Street::River => "River",
+        }
+    }
+}
+
+/// Helper to keep track of everything which is required to write a hand history.
+struct Writer<'a> {
+    info: &'a TableInfo,
+    variant: GameVariant,
+    lines: Vec<String>,
+    hands: Vec<Option<Vec<Card>>>,
+    dealt: Vec<usize>,
+    folded: Vec<Option<Street>>,
+    total_bets: Vec<ChipCount>,
+    street_bets: Vec<ChipCount>,
+    collected: Vec<ChipCount>,
+    board: Vec<Card>,
+    street: Street,
+    forced_bets: Vec<usize>,
+    hole_cards_written: bool,
+    showdown: bool,
+}
+
+/// Convert the actions of a single round into a hand history in the text format used by PokerStars.
+///
+/// The given `actions` are expected to describe exactly one round from start to finish,
+/// i. e. `TransparentState::actions` after the round was played. They may originate from a
+/// `Round` iterator as well.
+///
+/// Since chips do not have any associated value in this crate, the history is written as a
+/// play money game. Rounds of stud games are not supported.
+///
+/// ### Example:
+/// ```rust
+/// use poppy::actions::Action;
+/// use poppy::history::{to_pokerstars, TableInfo};
+/// use poppy::{BettingStructure, GameVariant};
+///
+/// let actions = vec![
+///     Action::StartRound {
+///         id: 1,
+///         big_blind: 2,
+///         small_blind: 1,
+///         dealer_position: 0,
+///         player_stacks: vec![100, 100],
+///         betting_structure: BettingStructure::NoLimit,
+///         variant: GameVariant::Holdem,
+///         ante: 0,
+///         bomb_pot: None,
+///     },
+///     Action::Blind(1, 1),
+///     Action::Blind(0, 2),
+///     Action::Fold(1),
+///     Action::Win(vec![(0, 3)]),
+/// ];
+/// let info = TableInfo::new("Poppy", vec!["Alice".to_string(), "Bob".to_string()]);
+/// let history = to_pokerstars(&actions, &info).unwrap();
+/// assert!(history.contains("Bob: posts small blind 1"));
+/// ```
+pub fn to_pokerstars(actions: &[Action], info: &TableInfo) -> Result<String, HistoryError> {
+    let (id, small_blind, big_blind, dealer_position, player_stacks, betting_structure, variant) =
+        match actions.first() {
+            Some(Action::StartRound {
+                bomb_pot: Some(_), ..
+            }) => return Err(HistoryError::Unsupported(String::from("bomb pots"))),
+            Some(Action::StartRound {
+                id,
+                small_blind,
+                big_blind,
+                dealer_position,
+                player_stacks,
+                betting_structure,
+                variant,
+                ..
+            }) => (
+                *id,
+                *small_blind,
+                *big_blind,
+                *dealer_position,
+                player_stacks,
+                *betting_structure,
+                *variant,
+            ),
+            _ => return Err(HistoryError::MissingStartRound),
+        };
+    let game_name = game_name(variant)
+        .ok_or_else(|| HistoryError::Unsupported(format!("game variant {:?}", variant)))?;
+
+    let num_players = player_stacks.len();
+    let mut writer = Writer {
+        info,
+        variant,
+        lines: Vec::new(),
+        hands: vec![None; num_players],
+        dealt: Vec::new(),
+        folded: vec![None; num_players],
+        total_bets: vec![0; num_players],
+        street_bets: vec![0; num_players],
+        collected: vec![0; num_players],
+        board: Vec::new(),
+        street: Street::PreFlop,
+        forced_bets: Vec::new(),
+        hole_cards_written: false,
+        showdown: false,
+    };
+
+    // limit games are described by the size of the small and the big bet instead of the blinds
+    let stakes = match betting_structure {
+        BettingStructure::FixedLimit { .. } => (big_blind, 2 * big_blind),
+        _ => (small_blind, big_blind),
+    };
+    writer.lines.push(format!(
+        "PokerStars Hand #{}:  {} {} ({}/{}) - {} ET",
+        id,
+        game_name,
+        limit_name(betting_structure),
+        stakes.0,
+        stakes.1,
+        info.start_time
+    ));
+    writer.lines.push(format!(
+        "Table '{}' {}-max Seat #{} is the button",
+        info.table_name,
+        num_players,
+        dealer_position + 1
+    ));
+
+    // only players which are dealt in are seated for this round, listed in order of their seats
+    let dealt_in: Vec<usize> = actions
+        .iter()
+        .filter_map(|action| match action {
+            Action::DealHand(pos, _) => Some(*pos),
+            _ => None,
+        })
+        .collect();
+    for pos in (0..num_players).filter(|pos| dealt_in.contains(pos)) {
+        writer.lines.push(format!(
+            "Seat {}: {} ({} in chips)",
+            pos + 1,
+            info.seat_name(pos)?,
+            player_stacks[pos]
+        ));
+    }
+
+    for action in actions[1..].iter() {
+        writer.write_action(action)?;
+        if let Action::Win(_) = action {
+            writer.write_summary(dealer_position)?;
+            break;
+        }
+    }
+
+    let mut history = writer.lines.join("\n");
+    history.push('\n');
+    Ok(history)
+}
+
+impl<'a> Writer<'a> {
+    fn write_action(&mut self, action: &Action) -> Result<(), HistoryError> {
+        match action {
+            Action::StartRound { .. }
+            | Action::DealUp(..)
+            | Action::DealDown(..)
+            | Action::BringIn(..) => return Err(HistoryError::UnexpectedAction(action.clone())),
+            Action::Run(..) => {
+                return Err(HistoryError::Unsupported(String::from(
+                    "running the board multiple times",
+                )))
+            }
+            Action::SecondBoard(_) => {
+                return Err(HistoryError::Unsupported(String::from("double boards")))
+            }
+            Action::Straddle(..) => {
+                return Err(HistoryError::Unsupported(String::from("straddles")))
+            }
+            Action::MissedBlinds(..) => {
+                return Err(HistoryError::Unsupported(String::from("missed blinds")))
+            }
+            Action::IncreaseBlind(_)
+            | Action::SitOut(_)
+            | Action::Return(_)
+            | Action::Timeout(_) => {}
+            Action::DealHand(pos, cards) => {
+                self.hands[*pos] = Some(cards.to_vec());
+                self.dealt.push(*pos);
+            }
+            Action::Ante(pos, c) => {
+                let line = format!("{}: posts the ante {}", self.info.seat_name(*pos)?, c);
+                self.lines.push(line);
+                // antes do not count as bets of the street
+                self.total_bets[*pos] += c;
+            }
+            Action::Blind(pos, c) => self.write_forced_bet(*pos, *c, false)?,
+            Action::AllIn(pos, c) if !self.hole_cards_written && self.forced_bets.len() < 2 => {
+                self.write_forced_bet(*pos, *c, true)?
+            }
+            Action::DealFlop(cards) => {
+                self.start_street(Street::Flop)?;
+                self.board.extend_from_slice(cards);
+                self.lines
+                    .push(format!("*** FLOP *** [{}]", format_cards(&self.board)));
+            }
+            Action::DealTurn(card) => {
+                self.start_street(Street::Turn)?;
+                self.lines.push(format!(
+                    "*** TURN *** [{}] [{}]",
+                    format_cards(&self.board),
+                    card
+                ));
+                self.board.push(*card);
+            }
+            Action::DealRiver(card) => {
+                self.start_street(Street::River)?;
+                self.lines.push(format!(
+                    "*** RIVER *** [{}] [{}]",
+                    format_cards(&self.board),
+                    card
+                ));
+                self.board.push(*card);
+            }
+            Action::Check(pos) => {
+                self.write_hole_cards()?;
+                let line = format!("{}: checks", self.info.seat_name(*pos)?);
+                self.lines.push(line);
+            }
+            Action::Fold(pos) => {
+                self.write_hole_cards()?;
+                self.folded[*pos] = Some(self.street);
+                let line = format!("{}: folds", self.info.seat_name(*pos)?);
+                self.lines.push(line);
+            }
+            Action::Call(pos, c)
+            | Action::Raise(pos, c)
+            | Action::AllIn(pos, c)
+            | Action::Bet(pos, c) => {
+                self.write_hole_cards()?;
+                let level = self.bet_level();
+                let total = self.street_bets[*pos] + c;
+                let description = match action {
+                    _ if total <= level => format!("calls {}", c),
+                    Action::Bet(..) => format!("bets {}", c),
+                    Action::AllIn(..) if level == 0 => format!("bets {}", c),
+                    _ => format!("raises {} to {}", total - level, total),
+                };
+                let all_in = if let Action::AllIn(..) = action {
+                    " and is all-in"
+                } else {
+                    ""
+                };
+                let line = format!("{}: {}{}", self.info.seat_name(*pos)?, description, all_in);
+                self.lines.push(line);
+                self.place_chips(*pos, *c);
+            }
+            Action::Win(wins) => {
+                self.write_hole_cards()?;
+                self.write_showdown(wins)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_forced_bet(
+        &mut self,
+        pos: usize,
+        c: ChipCount,
+        all_in: bool,
+    ) -> Result<(), HistoryError> {
+        let blind = if self.forced_bets.is_empty() {
+            "small blind"
+        } else {
+            "big blind"
+        };
+        let all_in = if all_in { " and is all-in" } else { "" };
+        let line = format!(
+            "{}: posts {} {}{}",
+            self.info.seat_name(pos)?,
+            blind,
+            c,
+            all_in
+        );
+        self.lines.push(line);
+        self.forced_bets.push(pos);
+        self.place_chips(pos, c);
+        Ok(())
+    }
+
+    fn write_hole_cards(&mut self) -> Result<(), HistoryError> {
+        if self.hole_cards_written {
+            return Ok(());
+        }
+        self.hole_cards_written = true;
+
+        self.lines.push(String::from("*** HOLE CARDS ***"));
+        for &pos in self.dealt.iter() {
+            if self.info.hero.map(|hero| hero == pos).unwrap_or(true) {
+                let line = format!(
+                    "Dealt to {} [{}]",
+                    self.info.seat_name(pos)?,
+                    format_cards(self.hands[pos].as_ref().unwrap())
+                );
+                self.lines.push(line);
+            }
+        }
+        Ok(())
+    }
+
+    fn start_street(&mut self, street: Street) -> Result<(), HistoryError> {
+        self.write_hole_cards()?;
+        self.street = street;
+        for bet in self.street_bets.iter_mut() {
+            *bet = 0;
+        }
+        Ok(())
+    }
+
+    fn write_showdown(&mut self, wins: &[(usize, ChipCount)]) -> Result<(), HistoryError> {
+        // The pot is distributed completely in the game, i. e. the part of a bet which is not
+        // called by anyone is "won" back. This is reported separately in the history.
+        let mut uncalled = None;
+        if let Some(top) = (0..self.total_bets.len()).max_by_key(|&pos| self.total_bets[pos]) {
+            let second = (0..self.total_bets.len())
+                .filter(|&pos| pos != top)
+                .map(|pos| self.total_bets[pos])
+                .max()
+                .unwrap_or(0);
+            if self.total_bets[top] > second {
+                let excess = self.total_bets[top] - second;
+                self.total_bets[top] = second;
+                let line = format!(
+                    "Uncalled bet ({}) returned to {}",
+                    excess,
+                    self.info.seat_name(top)?
+                );
+                self.lines.push(line);
+                uncalled = Some((top, excess));
+            }
+        }
+
+        let remaining = self.remaining_players();
+        if remaining.len() > 1 {
+            self.showdown = true;
+            self.lines.push(String::from("*** SHOW DOWN ***"));
+            for pos in remaining {
+                let line = format!(
+                    "{}: shows [{}] ({})",
+                    self.info.seat_name(pos)?,
+                    format_cards(self.hands[pos].as_ref().unwrap()),
+                    self.describe_hand(pos)
+                );
+                self.lines.push(line);
+            }
+        }
+
+        for &(pos, amount) in wins.iter() {
+            let amount = match uncalled.take() {
+                Some((top, excess)) if top == pos => amount.saturating_sub(excess),
+                other => {
+                    uncalled = other;
+                    amount
+                }
+            };
+            if amount > 0 {
+                self.collected[pos] += amount;
+                let line = format!(
+                    "{} collected {} from pot",
+                    self.info.seat_name(pos)?,
+                    amount
+                );
+                self.lines.push(line);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_summary(&mut self, dealer_position: usize) -> Result<(), HistoryError> {
+        self.lines.push(String::from("*** SUMMARY ***"));
+        self.lines.push(format!(
+            "Total pot {} | Rake 0",
+            self.total_bets.iter().sum::<ChipCount>()
+        ));
+        if !self.board.is_empty() {
+            self.lines
+                .push(format!("Board [{}]", format_cards(&self.board)));
+        }
+
+        for pos in 0..self.hands.len() {
+            let cards = match &self.hands[pos] {
+                Some(cards) => cards,
+                None => continue,
+            };
+
+            let role = if pos == dealer_position {
+                " (button)"
+            } else if self.forced_bets.first() == Some(&pos) {
+                " (small blind)"
+            } else if self.forced_bets.get(1) == Some(&pos) {
+                " (big blind)"
+            } else {
+                ""
+            };
+
+            let outcome = match self.folded[pos] {
+                Some(Street::PreFlop) if self.total_bets[pos] == 0 => {
+                    String::from("folded before Flop (didn't bet)")
+                }
+                Some(Street::PreFlop) => String::from("folded before Flop"),
+                Some(street) => format!("folded on the {}", street.name()),
+                None if self.showdown && self.collected[pos] > 0 => format!(
+                    "showed [{}] and won ({}) with {}",
+                    format_cards(cards),
+                    self.collected[pos],
+                    self.describe_hand(pos)
+                ),
+                None if self.showdown => format!(
+                    "showed [{}] and lost with {}",
+                    format_cards(cards),
+                    self.describe_hand(pos)
+                ),
+                None => format!("collected ({})", self.collected[pos]),
+            };
+
+            let line = format!(
+                "Seat {}: {}{} {}",
+                pos + 1,
+                self.info.seat_name(pos)?,
+                role,
+                outcome
+            );
+            self.lines.push(line);
+        }
+
+        Ok(())
+    }
+
+    fn place_chips(&mut self, pos: usize, c: ChipCount) {
+        self.street_bets[pos] += c;
+        self.total_bets[pos] += c;
+    }
+
+    fn bet_level(&self) -> ChipCount {
+        self.street_bets.iter().copied().max().unwrap_or(0)
+    }
+
+    fn remaining_players(&self) -> Vec<usize> {
+        self.dealt
+            .iter()
+            .copied()
+            .filter(|&pos| self.folded[pos].is_none())
+            .collect()
+    }
+
+    fn describe_hand(&self, pos: usize) -> &'static str {
+        let hand = self.hands[pos].as_ref().unwrap();
+        describe_rank(&self.variant.rank_hand(hand, &self.board))
+    }
+}
+
+/// Split a text containing multiple hand histories in the format used by PokerStars into the
+/// single hands.
+///
+/// The returned hands can be parsed using `from_pokerstars`.
+pub fn split_pokerstars(text: &str) -> Vec<&str> {
+    let mut hands = Vec::new();
+    let mut start = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if line
+            .trim_start_matches('\u{feff}')
+            .starts_with("PokerStars ")
+        {
+            if let Some(start) = start {
+                hands.push(text[start..offset].trim());
+            }
+            start = Some(offset);
+        }
+        offset += line.len();
+    }
+    if let Some(start) = start {
+        hands.push(text[start..].trim());
+    }
+    hands
+}
+
+/// Parse a single hand from a hand history in the text format used by PokerStars.
+///
+/// Only Hold'em hands are supported. Limit games are expected to be capped at four bets per
+/// street. Amounts of real money games are converted into cents, i. e. one chip corresponds
+/// to one cent.
+///
+/// The parsed hand is replayed in order to verify that it is consistent with the rules
+/// implemented by this crate. Note that the engine does not take rake, which is why the rake
+/// of the hand is attributed to the player collecting the pot. Raked pots won by several players
+/// are not supported.
+///
+/// Players who are sitting out are not considered part of the round, i. e. the positions
+/// of the imported hand do not necessarily correspond to the seat numbers of the hand history.
+///
+/// Heads-up hands are not supported, since the button posts the small blind heads-up while the
+/// engine always lets the player after the button post it.
+pub fn from_pokerstars(text: &str) -> Result<ImportedHand, HistoryError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim_start_matches('\u{feff}').trim_end()))
+        .filter(|(_, line)| !line.is_empty())
+        .peekable();
+
+    let (n, line) = lines.next().ok_or(HistoryError::MissingStartRound)?;
+    let header = Header::parse(n, line)?;
+
+    let (n, line) = lines.next().ok_or(HistoryError::MissingStartRound)?;
+    let (table_name, button) = parse_table_line(line).ok_or_else(|| invalid_line(n, line))?;
+
+    let mut parser = Parser {
+        scale: header.scale,
+        variant: header.variant,
+        names: Vec::new(),
+        seat_numbers: Vec::new(),
+        stacks: Vec::new(),
+        actions: Vec::new(),
+        hands: Vec::new(),
+        street_bets: Vec::new(),
+        wins: Vec::new(),
+        collectors: Vec::new(),
+        rake: 0,
+        ante: 0,
+        dealt_to: Vec::new(),
+        in_summary: false,
+    };
+
+    while let Some(&(n, line)) = lines.peek() {
+        if !line.starts_with("Seat ") || !line.contains(" in chips") {
+            break;
+        }
+        parser.parse_seat(n, line)?;
+        lines.next();
+    }
+
+    for (n, line) in lines {
+        parser.parse_line(n, line)?;
+    }
+
+    parser.into_hand(header, table_name, button)
+}
+
+/// The information contained in the first line of a hand.
+struct Header {
+    id: usize,
+    variant: GameVariant,
+    betting_structure: BettingStructure,
+    small_blind: ChipCount,
+    big_blind: ChipCount,
+    start_time: String,
+    scale: ChipCount,
+}
+
+impl Header {
+    fn parse(n: usize, line: &str) -> Result<Self, HistoryError> {
+        let invalid = || invalid_line(n, line);
+        if !line.starts_with("PokerStars ") {
+            return Err(invalid());
+        }
+
+        let id_start = line.find("Hand #").ok_or_else(invalid)? + "Hand #".len();
+        let id_end = id_start + line[id_start..].find(':').ok_or_else(invalid)?;
+        let id = line[id_start..id_end].parse().map_err(|_| invalid())?;
+
+        let (game_start, variant, betting_structure) = VARIANTS
+            .iter()
+            .flat_map(|&variant| {
+                BETTING_STRUCTURES
+                    .iter()
+                    .map(move |&structure| (variant, structure))
+            })
+            .find_map(|(variant, structure)| {
+                let game = format!("{} {} ", game_name(variant)?, limit_name(structure));
+                line.find(&game).map(|start| (start, variant, structure))
+            })
+            .ok_or_else(|| {
+                HistoryError::Unsupported(format!("game `{}`", line[id_end + 1..].trim()))
+            })?;
+
+        let blinds_start = game_start + line[game_start..].find('(').ok_or_else(invalid)? + 1;
+        let blinds_end = blinds_start + line[blinds_start..].find(')').ok_or_else(invalid)?;
+        let blinds = line[blinds_start..blinds_end]
+            .split_whitespace()
+            .next()
+            .ok_or_else(invalid)?;
+        let scale = if blinds.contains(CURRENCIES) { 100 } else { 1 };
+        let mut blinds = blinds.split('/').map(|b| parse_amount(b, scale));
+        let (small_blind, big_blind) = match (blinds.next(), blinds.next(), betting_structure) {
+            (Some(Some(small_bet)), Some(Some(big_bet)), BettingStructure::FixedLimit { .. }) => {
+                if big_bet != 2 * small_bet || small_bet % 2 != 0 {
+                    return Err(HistoryError::Unsupported(String::from(
+                        "bet sizes which do not translate into blinds",
+                    )));
+                }
+                (small_bet / 2, small_bet)
+            }
+            (Some(Some(small_blind)), Some(Some(big_blind)), _) => (small_blind, big_blind),
+            _ => return Err(invalid()),
+        };
+        if big_blind != 2 * small_blind {
+            return Err(HistoryError::Unsupported(String::from(
+                "big blind which is not twice the small blind",
+            )));
+        }
+
+        // prefer the time in ET if the hand was played in a different time zone
+        let time = match (line.rfind('['), line.ends_with(']')) {
+            (Some(start), true) => &line[start + 1..line.len() - 1],
+            _ => line.rsplit(" - ").next().ok_or_else(invalid)?,
+        };
+        let start_time = time
+            .split_whitespace()
+            .take(2)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Ok(Self {
+            id,
+            variant,
+            betting_structure,
+            small_blind,
+            big_blind,
+            start_time,
+            scale,
+        })
+    }
+}
+
+/// Helper to keep track of a hand while it is parsed.
+struct Parser {
+    scale: ChipCount,
+    variant: GameVariant,
+    names: Vec<String>,
+    seat_numbers: Vec<usize>,
+    stacks: Vec<ChipCount>,
+    actions: Vec<Action>,
+    hands: Vec<Option<Vec<Card>>>,
+    street_bets: Vec<ChipCount>,
+    wins: Vec<(usize, ChipCount)>,
+    // the players who collected a pot, as opposed to uncalled bets being returned
+    collectors: Vec<usize>,
+    rake: ChipCount,
+    ante: ChipCount,
+    dealt_to: Vec<usize>,
+    in_summary: bool,
+}
+
+impl Parser {
+    fn parse_seat(&mut self, n: usize, line: &str) -> Result<(), HistoryError> {
+        let invalid = || invalid_line(n, line);
+
+        let rest = &line["Seat ".len()..];
+        let colon = rest.find(": ").ok_or_else(invalid)?;
+        let seat_number = rest[..colon].parse().map_err(|_| invalid())?;
+        let rest = &rest[colon + 2..];
+
+        let chips_end = rest.find(" in chips").ok_or_else(invalid)?;
+        let name_end = rest[..chips_end].rfind(" (").ok_or_else(invalid)?;
+        let stack = parse_amount(&rest[name_end + 2..chips_end], self.scale).ok_or_else(invalid)?;
+
+        if rest.ends_with("is sitting out") || rest.ends_with("out of hand") {
+            // those players are not dealt in
+            return Ok(());
+        }
+
+        // positions are assigned in the order of the seat numbers
+        let pos = self
+            .seat_numbers
+            .iter()
+            .take_while(|&&seat| seat < seat_number)
+            .count();
+        self.names.insert(pos, rest[..name_end].to_string());
+        self.seat_numbers.insert(pos, seat_number);
+        self.stacks.insert(pos, stack);
+        self.hands.push(None);
+        self.street_bets.push(0);
+        Ok(())
+    }
+
+    fn parse_line(&mut self, n: usize, line: &str) -> Result<(), HistoryError> {
+        let invalid = || invalid_line(n, line);
+
+        if line.starts_with("*** ") {
+            if line.starts_with("*** FLOP ***") {
+                let cards = parse_last_cards(line).ok_or_else(invalid)?;
+                let cards = <[Card; 3]>::try_from(&cards[..]).map_err(|_| invalid())?;
+                self.start_street(Action::DealFlop(cards));
+            } else if line.starts_with("*** TURN ***") {
+                let cards = parse_last_cards(line).ok_or_else(invalid)?;
+                self.start_street(Action::DealTurn(single_card(&cards).ok_or_else(invalid)?));
+            } else if line.starts_with("*** RIVER ***") {
+                let cards = parse_last_cards(line).ok_or_else(invalid)?;
+                self.start_street(Action::DealRiver(single_card(&cards).ok_or_else(invalid)?));
+            } else if line == "*** SUMMARY ***" {
+                self.in_summary = true;
+            } else if line != "*** HOLE CARDS ***" && line != "*** SHOW DOWN ***" {
+                return Err(HistoryError::Unsupported(format!("street `{}`", line)));
+            }
+        } else if self.in_summary {
+            if let Some(rake_start) = line.find("| Rake ") {
+                let rake = line[rake_start + "| Rake ".len()..]
+                    .split_whitespace()
+                    .next()
+                    .ok_or_else(invalid)?;
+                self.rake = parse_amount(rake, self.scale).ok_or_else(invalid)?;
+            } else if let Some(rest) = line.strip_prefix("Seat ") {
+                let colon = rest.find(": ").ok_or_else(invalid)?;
+                let seat_number: usize = rest[..colon].parse().map_err(|_| invalid())?;
+                let revealed = rest.find("showed [").or_else(|| rest.find("mucked ["));
+                if let (Some(pos), Some(start)) = (
+                    self.seat_numbers.iter().position(|&s| s == seat_number),
+                    revealed,
+                ) {
+                    let cards = parse_cards(&rest[start..]).ok_or_else(invalid)?;
+                    self.reveal(pos, &cards).ok_or_else(invalid)?;
+                }
+            }
+        } else if let Some(rest) = line.strip_prefix("Dealt to ") {
+            if let Some((pos, _)) = self.find_player(rest, " [") {
+                let cards = parse_cards(line).ok_or_else(invalid)?;
+                self.reveal(pos, &cards).ok_or_else(invalid)?;
+                self.dealt_to.push(pos);
+            }
+        } else if let Some(rest) = line.strip_prefix("Uncalled bet (") {
+            let end = rest.find(')').ok_or_else(invalid)?;
+            let amount = parse_amount(&rest[..end], self.scale).ok_or_else(invalid)?;
+            let name = rest[end + 1..]
+                .strip_prefix(" returned to ")
+                .ok_or_else(invalid)?;
+            let pos = self
+                .names
+                .iter()
+                .position(|n| n == name)
+                .ok_or_else(invalid)?;
+            self.add_win(pos, amount);
+        } else if let Some((pos, rest)) = self.find_player(line, ": ") {
+            self.parse_player_action(pos, rest)
+                .unwrap_or_else(|| Err(invalid()))?;
+        } else if let Some((pos, rest)) = self.find_player(line, " collected ") {
+            let amount = rest.split_whitespace().next().ok_or_else(invalid)?;
+            let amount = parse_amount(amount, self.scale).ok_or_else(invalid)?;
+            self.add_win(pos, amount);
+            if !self.collectors.contains(&pos) {
+                self.collectors.push(pos);
+            }
+        }
+        // everything else (i. e. chat messages, players joining, ...) is of no interest
+
+        Ok(())
+    }
+
+    /// Parse the action of the player at `pos`.
+    ///
+    /// Returns `None` if the action could not be parsed.
+    fn parse_player_action(&mut self, pos: usize, rest: &str) -> Option<Result<(), HistoryError>> {
+        let (rest, all_in) = match rest.strip_suffix(" and is all-in") {
+            Some(rest) => (rest, true),
+            None => (rest, false),
+        };
+        let mut words = rest.split_whitespace();
+
+        let (action, placed) = match words.next()? {
+            "posts" if rest.starts_with("posts the ante ") => {
+                let amount = parse_amount(words.nth(2)?, self.scale)?;
+                self.ante = self.ante.max(amount);
+                self.actions.push(Action::Ante(pos, amount));
+                return Some(Ok(()));
+            }
+            "posts" => {
+                let amount = match (words.next(), words.next(), words.next()) {
+                    (Some("small"), Some("blind"), Some(amount))
+                    | (Some("big"), Some("blind"), Some(amount)) => amount,
+                    _ => {
+                        return Some(Err(HistoryError::Unsupported(format!(
+                            "forced bet `{}`",
+                            rest
+                        ))))
+                    }
+                };
+                let amount = parse_amount(amount, self.scale)?;
+                (Action::Blind(pos, amount), amount)
+            }
+            "folds" => {
+                if rest.contains('[') {
+                    let cards = parse_cards(rest)?;
+                    self.reveal(pos, &cards)?;
+                }
+                (Action::Fold(pos), 0)
+            }
+            "checks" => (Action::Check(pos), 0),
+            "calls" => {
+                let amount = parse_amount(words.next()?, self.scale)?;
+                (Action::Call(pos, amount), amount)
+            }
+            "bets" => {
+                let amount = parse_amount(words.next()?, self.scale)?;
+                (Action::Bet(pos, amount), amount)
+            }
+            "raises" => {
+                let (_, to) = (words.next()?, words.next()?);
+                let total = parse_amount(words.next()?, self.scale)?;
+                if to != "to" || total < self.street_bets[pos] {
+                    return None;
+                }
+                let amount = total - self.street_bets[pos];
+                (Action::Raise(pos, amount), amount)
+            }
+            "shows" => {
+                let cards = parse_cards(rest)?;
+                self.reveal(pos, &cards)?;
+                return Some(Ok(()));
+            }
+            _ => return Some(Ok(())),
+        };
+
+        let action = if all_in {
+            Action::AllIn(pos, placed)
+        } else {
+            action
+        };
+        self.street_bets[pos] += placed;
+        self.actions.push(action);
+        Some(Ok(()))
+    }
+
+    fn start_street(&mut self, deal: Action) {
+        for bet in self.street_bets.iter_mut() {
+            *bet = 0;
+        }
+        self.actions.push(deal);
+    }
+
+    fn reveal(&mut self, pos: usize, cards: &[Card]) -> Option<()> {
+        if cards.len() != self.variant.hole_cards() {
+            return None;
+        }
+        self.hands[pos] = Some(cards.to_vec());
+        Some(())
+    }
+
+    fn add_win(&mut self, pos: usize, amount: ChipCount) {
+        match self.wins.iter_mut().find(|(p, _)| *p == pos) {
+            Some((_, total)) => *total += amount,
+            None => self.wins.push((pos, amount)),
+        }
+    }
+
+    /// Find the player whose name is followed by `separator` at the start of the given line.
+    ///
+    /// Returns the player's position and the rest of the line after the separator.
+    fn find_player<'l>(&self, line: &'l str, separator: &str) -> Option<(usize, &'l str)> {
+        self.names
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| {
+                line.starts_with(name.as_str()) && line[name.len()..].starts_with(separator)
+            })
+            .max_by_key(|(_, name)| name.len())
+            .map(|(pos, name)| (pos, &line[name.len() + separator.len()..]))
+    }
+
+    fn into_hand(
+        mut self,
+        header: Header,
+        table_name: String,
+        button: usize,
+    ) -> Result<ImportedHand, HistoryError> {
+        let dealer_position = self
+            .seat_numbers
+            .iter()
+            .position(|&seat| seat == button)
+            .ok_or_else(|| HistoryError::Unsupported(String::from("dead button")))?;
+
+        // the engine does not take rake, it can only be attributed to a single winner
+        match self.collectors.as_slice() {
+            _ if self.rake == 0 => {}
+            &[collector] => self.add_win(collector, self.rake),
+            _ => {
+                return Err(HistoryError::Unsupported(String::from(
+                    "rake of pots won by several players",
+                )))
+            }
+        }
+
+        let start = Action::StartRound {
+            id: header.id,
+            big_blind: header.big_blind,
+            small_blind: header.small_blind,
+            betting_structure: header.betting_structure,
+            variant: header.variant,
+            ante: self.ante,
+            bomb_pot: None,
+            dealer_position,
+            player_stacks: self.stacks,
+        };
+        let info = TableInfo {
+            table_name,
+            seat_names: self.names,
+            start_time: header.start_time,
+            hero: if self.dealt_to.len() == 1 {
+                self.dealt_to.first().copied()
+            } else {
+                None
+            },
+        };
+
+        ImportedHand::assemble(info, start, &self.hands, self.actions, self.wins)
+    }
+}
+
+/// The betting structures which can be imported.
+///
+/// Limit games are capped at four bets per street.
+const BETTING_STRUCTURES: &[BettingStructure] = &[
+    BettingStructure::NoLimit,
+    BettingStructure::PotLimit,
+    BettingStructure::FixedLimit { raise_cap: 4 },
+];
+
+/// The game variants which can be imported.
+///
+/// Short deck has to be matched before Hold'em, since its name contains the name of Hold'em.
+const VARIANTS: &[GameVariant] = &[
+    GameVariant::ShortDeck,
+    GameVariant::Holdem,
+    GameVariant::Omaha,
+    GameVariant::OmahaHiLo,
+];
+
+/// The name of the game variant as it appears in the description of the game.
+///
+/// Returns `None` for variants which are not supported.
+fn game_name(variant: GameVariant) -> Option<&'static str> {
+    match variant {
+        GameVariant::Holdem => Some("Hold'em"),
+        GameVariant::Omaha => Some("Omaha"),
+        GameVariant::OmahaHiLo => Some("Omaha Hi/Lo"),
+        GameVariant::ShortDeck => Some("6+ Hold'em"),
+        GameVariant::SevenCardStud => None,
+    }
+}
+
+/// The name of the betting structure as it appears in the description of the game.
+fn limit_name(betting_structure: BettingStructure) -> &'static str {
+    match betting_structure {
+        BettingStructure::NoLimit => "No Limit",
+        BettingStructure::PotLimit => "Pot Limit",
+        BettingStructure::FixedLimit { .. } => "Limit",
+    }
+}
+
+/// Currency symbols of real money games
+const CURRENCIES: &[char] = &['$', '€', '£'];
+
+fn invalid_line(line: usize, content: &str) -> HistoryError {
+    HistoryError::InvalidLine {
+        line,
+        content: content.to_string(),
+    }
+}
+
+/// Parse the table line, returning the name of the table and the seat number of the button.
+fn parse_table_line(line: &str) -> Option<(String, usize)> {
+    let rest = line.strip_prefix("Table '")?;
+    let name_end = rest.rfind('\'')?;
+    let button_start = rest.find("Seat #")? + "Seat #".len();
+    let button = rest[button_start..]
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()?;
+    Some((rest[..name_end].to_string(), button))
+}
+
+/// Parse an amount of chips. If `scale` is 100 the amount is a real money amount which is converted into cents.
+fn parse_amount(amount: &str, scale: ChipCount) -> Option<ChipCount> {
+    let amount = amount.trim_matches(CURRENCIES).replace(',', "");
+    if scale == 1 {
+        return amount.parse().ok();
+    }
+
+    let mut parts = amount.splitn(2, '.');
+    let whole: ChipCount = parts.next()?.parse().ok()?;
+    let cents = match parts.next() {
+        Some(cents) if cents.len() == 1 => cents.parse::<ChipCount>().ok()? * 10,
+        Some(cents) if cents.len() == 2 => cents.parse().ok()?,
+        Some(_) => return None,
+        None => 0,
+    };
+    Some(whole * scale + cents)
+}
+
+/// Parse the cards in the first pair of brackets of the given text.
+fn parse_cards(text: &str) -> Option<Vec<Card>> {
+    let start = text.find('[')? + 1;
+    let end = start + text[start..].find(']')?;
+    let cards: String = text[start..end].split_whitespace().collect();
+    CardCollection::try_from(cards.as_str())
+        .ok()
+        .map(|cards| cards.to_vec())
+}
+
+/// Parse the cards in the last pair of brackets of the given text.
+fn parse_last_cards(text: &str) -> Option<Vec<Card>> {
+    parse_cards(&text[text.rfind('[')?..])
+}
+
+fn single_card(cards: &[Card]) -> Option<Card> {
+    match cards {
+        [card] => Some(*card),
+        _ => None,
+    }
+}
+
+fn format_cards(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::PlayerAction;
+    use crate::mock::MockPlayer;
+    use crate::{BlindPolicy, Table};
+    use std::convert::TryInto;
+
+    fn table_info() -> TableInfo {
+        TableInfo::new(
+            "Poppy",
+            vec![
+                String::from("Alice"),
+                String::from("Bob"),
+                String::from("Carol"),
+                String::from("Dave"),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_missing_start_round() {
+        assert_eq!(
+            to_pokerstars(&[Action::Fold(0)], &table_info()),
+            Err(HistoryError::MissingStartRound)
+        );
+    }
+
+    #[test]
+    fn test_unknown_seat() {
+        let info = TableInfo::new("Poppy", vec![String::from("Alice")]);
+        let actions = [
+            Action::StartRound {
+                id: 1,
+                big_blind: 2,
+                small_blind: 1,
+                dealer_position: 0,
+                player_stacks: vec![100, 100],
+                betting_structure: BettingStructure::NoLimit,
+                variant: GameVariant::Holdem,
+                ante: 0,
+                bomb_pot: None,
+            },
+            Action::Blind(1, 1),
+        ];
+        assert_eq!(
+            to_pokerstars(&actions, &info),
+            Err(HistoryError::UnknownSeat(1))
+        );
+    }
+
+    #[test]
+    fn test_all_but_one_fold_pre_flop() {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Raise(10)]), // dealer
+            MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Fold]), // small
+            MockPlayer::new(vec![PlayerAction::Blind(2), PlayerAction::Fold]), // big
+            MockPlayer::new(vec![PlayerAction::Fold]),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+
+        let mut info = table_info();
+        info.hero = Some(0);
+        let history = to_pokerstars(&actions, &info).unwrap();
+        let lines: Vec<&str> = history.lines().collect();
+
+        assert_eq!(
+            lines,
+            [
+                "PokerStars Hand #1:  Hold'em No Limit (1/2) - 1970/01/01 00:00:00 ET",
+                "Table 'Poppy' 4-max Seat #1 is the button",
+                "Seat 1: Alice (100 in chips)",
+                "Seat 2: Bob (100 in chips)",
+                "Seat 3: Carol (100 in chips)",
+                "Seat 4: Dave (100 in chips)",
+                "Bob: posts small blind 1",
+                "Carol: posts big blind 2",
+                "*** HOLE CARDS ***",
+                "Dealt to Alice [Kc Ks]",
+                "Dave: folds",
+                "Alice: raises 8 to 10",
+                "Bob: folds",
+                "Carol: folds",
+                "Uncalled bet (8) returned to Alice",
+                "Alice collected 5 from pot",
+                "*** SUMMARY ***",
+                "Total pot 5 | Rake 0",
+                "Seat 1: Alice (button) collected (5)",
+                "Seat 2: Bob (small blind) folded before Flop",
+                "Seat 3: Carol (big blind) folded before Flop",
+                "Seat 4: Dave folded before Flop (didn't bet)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_showdown() {
+        // see `table::tests::test_play_showdown_and_one_player_wins`
+        let deck: CardCollection = "Jc9dTcQd2s2c8cKsQsTdTh7sAd".try_into().unwrap();
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Raise(10), PlayerAction::Fold]), // dealer
+            MockPlayer::new(vec![
+                PlayerAction::Blind(1),
+                PlayerAction::Call(9),
+                PlayerAction::Check, // after flop
+                PlayerAction::Call(2),
+                PlayerAction::Bet(2), // after turn
+                PlayerAction::Bet(2), // after river
+                PlayerAction::AllIn(86),
+            ]), // small
+            MockPlayer::new(vec![
+                PlayerAction::Blind(2),
+                PlayerAction::Call(8),
+                PlayerAction::Bet(2),    // after flop
+                PlayerAction::Call(2),   // after turn
+                PlayerAction::Raise(10), // after river
+                PlayerAction::AllIn(76),
+            ]), // big
+            MockPlayer::new(vec![PlayerAction::Fold]),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+        let actions: Vec<Action> = table.play_one_round(deck).unwrap().collect();
+
+        let history = to_pokerstars(&actions, &table_info()).unwrap();
+        let lines: Vec<&str> = history.lines().collect();
+
+        assert_eq!(
+            lines[8..],
+            [
+                "*** HOLE CARDS ***",
+                "Dealt to Bob [Ad 7s]",
+                "Dealt to Carol [Th Td]",
+                "Dealt to Dave [Qs Ks]",
+                "Dealt to Alice [8c 2c]",
+                "Dave: folds",
+                "Alice: raises 8 to 10",
+                "Bob: calls 9",
+                "Carol: calls 8",
+                "*** FLOP *** [2s Qd Tc]",
+                "Bob: checks",
+                "Carol: bets 2",
+                "Alice: folds",
+                "Bob: calls 2",
+                "*** TURN *** [2s Qd Tc] [9d]",
+                "Bob: bets 2",
+                "Carol: calls 2",
+                "*** RIVER *** [2s Qd Tc 9d] [Jc]",
+                "Bob: bets 2",
+                "Carol: raises 8 to 10",
+                "Bob: raises 76 to 86 and is all-in",
+                "Carol: calls 76 and is all-in",
+                "*** SHOW DOWN ***",
+                "Bob: shows [Ad 7s] (high card)",
+                "Carol: shows [Th Td] (three of a kind)",
+                "Carol collected 210 from pot",
+                "*** SUMMARY ***",
+                "Total pot 210 | Rake 0",
+                "Board [2s Qd Tc 9d Jc]",
+                "Seat 1: Alice (button) folded on the Flop",
+                "Seat 2: Bob (small blind) showed [Ad 7s] and lost with high card",
+                "Seat 3: Carol (big blind) showed [Th Td] and won (210) with three of a kind",
+                "Seat 4: Dave folded before Flop (didn't bet)",
+            ]
+        );
+    }
+
+    fn showdown_actions() -> Vec<Action> {
+        let deck: CardCollection = "Jc9dTcQd2s2c8cKsQsTdTh7sAd".try_into().unwrap();
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Raise(10), PlayerAction::Fold]), // dealer
+            MockPlayer::new(vec![
+                PlayerAction::Blind(1),
+                PlayerAction::Call(9),
+                PlayerAction::Check,
+                PlayerAction::Call(2),
+                PlayerAction::Bet(2),
+                PlayerAction::Bet(2),
+                PlayerAction::AllIn(86),
+            ]), // small
+            MockPlayer::new(vec![
+                PlayerAction::Blind(2),
+                PlayerAction::Call(8),
+                PlayerAction::Bet(2),
+                PlayerAction::Call(2),
+                PlayerAction::Raise(10),
+                PlayerAction::AllIn(76),
+            ]), // big
+            MockPlayer::new(vec![PlayerAction::Fold]),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+        let actions = table.play_one_round(deck).unwrap().collect();
+        actions
+    }
+
+    #[test]
+    fn test_import_exported_history() {
+        let actions = showdown_actions();
+        let history = to_pokerstars(&actions, &table_info()).unwrap();
+
+        let hand = from_pokerstars(&history).unwrap();
+        assert_eq!(hand.actions, actions);
+        assert_eq!(hand.info, table_info());
+        assert_eq!(hand.state.player_stacks, [90, 0, 210, 100]);
+    }
+
+    #[test]
+    fn test_pot_limit_round_trip() {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Raise(9)]), // dealer
+            MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Fold]), // small
+            MockPlayer::new(vec![PlayerAction::Blind(2), PlayerAction::Fold]), // big
+            MockPlayer::new(vec![PlayerAction::Fold]),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease)
+            .with_betting_structure(BettingStructure::PotLimit);
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+
+        let history = to_pokerstars(&actions, &table_info()).unwrap();
+        assert!(history.starts_with("PokerStars Hand #1:  Hold'em Pot Limit (1/2)"));
+        assert_eq!(from_pokerstars(&history).unwrap().actions, actions);
+    }
+
+    #[test]
+    fn test_fixed_limit_round_trip() {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Raise(4)]), // dealer
+            MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Fold]), // small
+            MockPlayer::new(vec![PlayerAction::Blind(2), PlayerAction::Fold]), // big
+            MockPlayer::new(vec![PlayerAction::Fold]),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease)
+            .with_betting_structure(BettingStructure::FixedLimit { raise_cap: 4 });
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+
+        let history = to_pokerstars(&actions, &table_info()).unwrap();
+        assert!(history.starts_with("PokerStars Hand #1:  Hold'em Limit (2/4)"));
+        assert_eq!(from_pokerstars(&history).unwrap().actions, actions);
+    }
+
+    #[test]
+    fn test_short_deck_ante_round_trip() {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Ante(1), PlayerAction::Raise(6)]), // dealer
+            MockPlayer::new(vec![
+                PlayerAction::Ante(1),
+                PlayerAction::Blind(1),
+                PlayerAction::Fold,
+            ]), // small
+            MockPlayer::new(vec![
+                PlayerAction::Ante(1),
+                PlayerAction::Blind(2),
+                PlayerAction::Fold,
+            ]), // big
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease)
+            .with_variant(GameVariant::ShortDeck)
+            .with_ante(1);
+        let actions: Vec<Action> = table
+            .play_one_round(GameVariant::ShortDeck.deck())
+            .unwrap()
+            .collect();
+
+        let history = to_pokerstars(&actions, &table_info()).unwrap();
+        assert!(history.starts_with("PokerStars Hand #1:  6+ Hold'em No Limit (1/2)"));
+        assert!(history.contains(": posts the ante 1"));
+        assert_eq!(from_pokerstars(&history).unwrap().actions, actions);
+    }
+
+    #[test]
+    fn test_omaha_round_trip() {
+        let players = vec![
+            MockPlayer::new(vec![
+                PlayerAction::Call(2),
+                PlayerAction::Check,
+                PlayerAction::Check,
+                PlayerAction::Check,
+            ]), // dealer
+            MockPlayer::new(vec![
+                PlayerAction::Blind(1),
+                PlayerAction::Call(1),
+                PlayerAction::Check,
+                PlayerAction::Check,
+                PlayerAction::Check,
+            ]), // small
+            MockPlayer::new(vec![
+                PlayerAction::Blind(2),
+                PlayerAction::Check,
+                PlayerAction::Check,
+                PlayerAction::Check,
+                PlayerAction::Check,
+            ]), // big
+            MockPlayer::new(vec![PlayerAction::Fold]),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease)
+            .with_betting_structure(BettingStructure::PotLimit)
+            .with_variant(GameVariant::Omaha);
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+
+        let history = to_pokerstars(&actions, &table_info()).unwrap();
+        assert!(history.starts_with("PokerStars Hand #1:  Omaha Pot Limit (1/2)"));
+        // exactly two of the aces are played
+        assert!(history.contains("Bob: shows [Ad Ah Ac As] (a full house)"));
+        assert_eq!(from_pokerstars(&history).unwrap().actions, actions);
+
+        // there is no qualifying low hand, hence the result is the same in Omaha Hi-Lo
+        let history = history.replace("Omaha Pot Limit", "Omaha Hi/Lo Pot Limit");
+        let hand = from_pokerstars(&history).unwrap();
+        assert!(matches!(
+            hand.actions[0],
+            Action::StartRound {
+                variant: GameVariant::OmahaHiLo,
+                ..
+            }
+        ));
+    }
+
+    const SAMPLE: &str = "\
+PokerStars Hand #219876543210:  Hold'em No Limit ($0.01/$0.02 USD) - 2020/09/12 14:03:21 CET [2020/09/12 8:03:21 ET]
+Table 'Aludra IV' 6-max Seat #2 is the button
+Seat 1: Hero ($2 in chips)
+Seat 2: villain_1 ($1.12 in chips)
+Seat 4: villain 2 ($2.41 in chips)
+Seat 5: sleepy ($2 in chips) is sitting out
+Seat 6: nit ($0.80 in chips)
+villain 2: posts small blind $0.01
+nit: posts big blind $0.02
+*** HOLE CARDS ***
+Dealt to Hero [Ah Kd]
+Hero: raises $0.04 to $0.06
+villain_1: calls $0.06
+villain 2: folds
+nit: folds
+*** FLOP *** [7c 8h Kc]
+Hero: bets $0.09
+villain_1: calls $0.09
+*** TURN *** [7c 8h Kc] [2d]
+Hero: bets $0.25
+villain_1: folds
+Uncalled bet ($0.25) returned to Hero
+Hero collected $0.32 from pot
+Hero: doesn't show hand
+*** SUMMARY ***
+Total pot $0.33 | Rake $0.01
+Board [7c 8h Kc 2d]
+Seat 1: Hero collected ($0.32)
+Seat 2: villain_1 (button) folded on the Turn
+Seat 4: villain 2 (small blind) folded before Flop
+Seat 6: nit (big blind) folded before Flop
+";
+
+    #[test]
+    fn test_import_sample() {
+        let hand = from_pokerstars(SAMPLE).unwrap();
+
+        assert_eq!(hand.info.table_name, "Aludra IV");
+        assert_eq!(hand.info.start_time, "2020/09/12 8:03:21");
+        assert_eq!(hand.info.hero, Some(0));
+        assert_eq!(
+            hand.info.seat_names,
+            ["Hero", "villain_1", "villain 2", "nit"]
+        );
+        assert_eq!(
+            hand.actions[0],
+            Action::StartRound {
+                id: 219876543210,
+                big_blind: 2,
+                small_blind: 1,
+                dealer_position: 1,
+                player_stacks: vec![200, 112, 241, 80],
+                betting_structure: BettingStructure::NoLimit,
+                variant: GameVariant::Holdem,
+                ante: 0,
+                bomb_pot: None,
+            }
+        );
+        let hero: CardCollection = "AhKd".try_into().unwrap();
+        assert_eq!(hand.actions[3], Action::DealHand(0, vec![hero[0], hero[1]]));
+        assert_eq!(hand.actions.last(), Some(&Action::Win(vec![(0, 58)])));
+        assert_eq!(hand.state.player_stacks, [218, 97, 240, 78]);
+    }
+
+    #[test]
+    fn test_split() {
+        let text = format!("\u{feff}{}\n\n\n{}\n", SAMPLE, SAMPLE);
+        let hands = split_pokerstars(&text);
+        assert_eq!(hands.len(), 2);
+        assert_eq!(hands[1], SAMPLE.trim());
+        assert!(from_pokerstars(hands[0]).is_ok());
+    }
+
+    #[test]
+    fn test_import_unsupported() {
+        let history = SAMPLE.replace(
+            "nit: posts big blind $0.02",
+            "nit: posts big blind $0.02\nHero: posts small & big blinds $0.03",
+        );
+        assert!(matches!(
+            from_pokerstars(&history),
+            Err(HistoryError::Unsupported(_))
+        ));
+
+        let history = SAMPLE.replace("Hold'em No Limit", "5 Card Draw No Limit");
+        assert!(matches!(
+            from_pokerstars(&history),
+            Err(HistoryError::Unsupported(_))
+        ));
+
+        let history = "\
+PokerStars Hand #219876543211:  Hold'em No Limit ($0.01/$0.02 USD) - 2020/09/12 14:05:02 CET [2020/09/12 8:05:02 ET]
+Table 'Aludra IV' 6-max Seat #1 is the button
+Seat 1: Hero ($2 in chips)
+Seat 2: villain_1 ($1.12 in chips)
+Hero: posts small blind $0.01
+villain_1: posts big blind $0.02
+*** HOLE CARDS ***
+Dealt to Hero [Ah Kd]
+Hero: folds
+Uncalled bet ($0.01) returned to villain_1
+villain_1 collected $0.02 from pot
+*** SUMMARY ***
+Total pot $0.02 | Rake $0
+Seat 1: Hero (button) (small blind) folded before Flop
+Seat 2: villain_1 (big blind) collected ($0.02)
+";
+        assert_eq!(
+            from_pokerstars(history).unwrap_err(),
+            HistoryError::Unsupported(String::from(
+                "heads-up play with the button posting the small blind"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_export_stud_unsupported() {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Fold]), // dealer
+            MockPlayer::new(vec![PlayerAction::BringIn(1)]),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease)
+            .with_variant(GameVariant::SevenCardStud);
+        let actions: Vec<Action> = table
+            .play_one_stud_round(CardCollection::default())
+            .unwrap()
+            .collect();
+
+        assert!(matches!(
+            to_pokerstars(&actions, &table_info()),
+            Err(HistoryError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_import_raked_split_pot() {
+        // the board is a royal flush, the pot is split between the players left
+        let deck: CardCollection = "ThJhQhKhAh3d2d5c4c3s2s".try_into().unwrap();
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Fold]), // dealer
+            MockPlayer::new(vec![
+                PlayerAction::Blind(1),
+                PlayerAction::Call(1),
+                PlayerAction::Check,
+                PlayerAction::Check,
+                PlayerAction::Check,
+            ]), // small
+            MockPlayer::new(vec![
+                PlayerAction::Blind(2),
+                PlayerAction::Check,
+                PlayerAction::Check,
+                PlayerAction::Check,
+                PlayerAction::Check,
+            ]), // big
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+        let actions: Vec<Action> = table.play_one_round(deck).unwrap().collect();
+        assert_eq!(actions.last(), Some(&Action::Win(vec![(2, 2), (1, 2)])));
+
+        let history = to_pokerstars(&actions, &table_info()).unwrap();
+        assert_eq!(from_pokerstars(&history).unwrap().actions, actions);
+
+        assert!(history.contains("Carol collected 2 from pot"));
+        let history = history
+            .replace("Rake 0", "Rake 2")
+            .replace("collected 2 from pot", "collected 1 from pot")
+            .replace("won (2)", "won (1)");
+        assert!(matches!(
+            from_pokerstars(&history),
+            Err(HistoryError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_import_raked_pot_with_uncalled_bet() {
+        let history = SAMPLE.replace(
+            "\
+Hero: bets $0.25
+villain_1: folds
+Uncalled bet ($0.25) returned to Hero
+Hero collected $0.32 from pot
+Hero: doesn't show hand
+*** SUMMARY ***
+Total pot $0.33 | Rake $0.01
+Board [7c 8h Kc 2d]
+Seat 1: Hero collected ($0.32)
+Seat 2: villain_1 (button) folded on the Turn
+",
+            "\
+Hero: checks
+villain_1: checks
+*** RIVER *** [7c 8h Kc 2d] [3s]
+Hero: bets $1.20
+villain_1: calls $0.97 and is all-in
+Uncalled bet ($0.23) returned to Hero
+*** SHOW DOWN ***
+Hero: shows [Ah Kd] (a pair of Kings)
+villain_1: shows [7d 7h] (three of a kind, Sevens)
+villain_1 collected $2.26 from pot
+*** SUMMARY ***
+Total pot $2.27 | Rake $0.01
+Board [7c 8h Kc 2d 3s]
+Seat 1: Hero showed [Ah Kd] and lost with a pair of Kings
+Seat 2: villain_1 (button) showed [7d 7h] and won ($2.26) with three of a kind, Sevens
+",
+        );
+        let hand = from_pokerstars(&history).unwrap();
+
+        // the rake is attributed to the winner of the pot, not to the player getting chips back
+        assert_eq!(
+            hand.actions.last(),
+            Some(&Action::Win(vec![(0, 23), (1, 227)]))
+        );
+        assert_eq!(hand.state.player_stacks, [88, 227, 240, 78]);
+    }
+
+    #[test]
+    fn test_import_invalid_line() {
+        let history = SAMPLE.replace("calls $0.06", "calls $0.0x");
+        assert_eq!(
+            from_pokerstars(&history).unwrap_err(),
+            HistoryError::InvalidLine {
+                line: 13,
+                content: String::from("villain_1: calls $0.0x"),
+            }
+        );
+    }
+
+    #[test]
+    fn test_import_inconsistent() {
+        // the big blind may not fold without facing a raise
+        let history = SAMPLE
+            .replace("Hero: raises $0.04 to $0.06", "Hero: calls $0.02")
+            .replace("villain_1: calls $0.06", "villain_1: calls $0.02");
+        let error = from_pokerstars(&history).unwrap_err();
+        assert!(matches!(error, HistoryError::Inconsistent(_)));
+    }
+}
@@ -1,7 +1,8 @@
 //! This is a simple library which can be used to implement no-limit Texas Hold'em poker gameplay in rust.
 //! Originally built on top of [rs_poker](https://crates.io/crates/rs-poker) the projects diverged quite
 //! a lot eventually resulting in a stand-alone library.
-//! There are no dependencies required, though adding serialization support is planned (as a feature).
+//! There are no dependencies required. Hand histories in the Open Hand History JSON format are
+//! supported with the `serde` feature.
 //!
 //!
 //! The gameplay is built as an iterator.
@@ -55,7 +56,7 @@
 //!     // You can shuffle decks as you want to.
 //!     // We will use a default un-sorted deck here.
 //!     let deck = deck::CardCollection::default();
-//!     let mut round_iter = table.play_one_round(deck);
+//!     let mut round_iter = table.play_one_round(deck).unwrap();
 //!
 //!     for action_taken in &mut round_iter {
 //!         println!("{:?}", action_taken);
@@ -78,25 +79,44 @@ pub type ChipCount = u32;
 mod mock;
 
 pub mod actions;
+mod asynchronous;
+mod betting;
 mod board;
 pub mod deck;
+pub mod history;
+mod observer;
 mod play;
 mod player;
 mod pot;
+pub mod replay;
 mod state;
+mod stepped;
+mod stud;
+mod summary;
 mod table;
+mod timing;
+mod variant;
 
+pub use asynchronous::{AsyncPlayer, AsyncRound, AsyncSeat, Decision};
+pub use betting::{BettingStructure, MissedBlinds, Straddle};
 pub use board::Board;
+pub use observer::TableObserver;
 pub use play::{Round, RoundCheckpoint};
 pub use player::Player;
 pub use pot::Pot;
 pub use state::{CheckpointState, TransparentState};
-pub use table::{BlindPolicy, Table};
+pub use stepped::{ExternalPlayer, InvalidDecision, Step, SteppedRound};
+pub use stud::StudRound;
+pub use summary::RoundSummary;
+pub use table::{BlindPolicy, CashGame, LedgerEntry, RoundOptions, Table};
+pub use timing::{TimeLimit, TimedPlayer, TimedRound};
+pub use variant::GameVariant;
 
 pub mod prelude {
     //! Module containing common imports required for basic usage.
     pub use super::{
-        actions::PlayerAction, deck, BlindPolicy, ChipCount, Player, Table, TransparentState,
+        actions::PlayerAction, deck, BettingStructure, BlindPolicy, ChipCount, GameVariant, Player,
+        Table, TransparentState,
     };
 }
 
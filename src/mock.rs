@@ -1,15 +1,18 @@
-use crate::actions::PlayerAction;
-use crate::deck::Card;
-use crate::{ChipCount, Player, TransparentState};
+use crate::actions::{Action, PlayerAction};
+use crate::{ChipCount, Player, RoundSummary, TransparentState};
 use std::collections::VecDeque;
 
 #[derive(Debug, Clone)]
 pub(crate) struct MockPlayer {
-    pub(crate) cards: Option<[Card; 2]>,
     pub(crate) position: Option<usize>,
     pub(crate) next_actions: VecDeque<PlayerAction>,
     pub(crate) busted: bool,
     pub(crate) last_possible_actions: Vec<PlayerAction>,
+    pub(crate) runs: usize,
+    pub(crate) straddles: bool,
+    pub(crate) rebuy: Option<ChipCount>,
+    pub(crate) observed: Vec<Action>,
+    pub(crate) summary: Option<RoundSummary>,
 }
 
 impl MockPlayer {
@@ -17,10 +20,14 @@ impl MockPlayer {
         let next_actions = VecDeque::from(next_actions);
         Self {
             position: None,
-            cards: None,
             busted: false,
             next_actions,
             last_possible_actions: Vec::new(),
+            runs: 1,
+            straddles: false,
+            rebuy: None,
+            observed: Vec::new(),
+            summary: None,
         }
     }
 }
@@ -47,6 +54,31 @@ impl Player for MockPlayer {
         action_taken
     }
 
+    fn straddle(&mut self, _state: &TransparentState, _size: ChipCount) -> bool {
+        self.straddles
+    }
+
+    fn run_count(&mut self, _state: &TransparentState, max_runs: usize) -> usize {
+        std::cmp::min(self.runs, max_runs)
+    }
+
+    fn rebuy(
+        &mut self,
+        _state: &TransparentState,
+        _min_buy_in: ChipCount,
+        _max_buy_in: ChipCount,
+    ) -> Option<ChipCount> {
+        self.rebuy
+    }
+
+    fn observe(&mut self, action: &Action) {
+        self.observed.push(action.clone());
+    }
+
+    fn round_finished(&mut self, summary: &RoundSummary) {
+        self.summary = Some(summary.clone());
+    }
+
     fn bust(&mut self) {
         self.busted = true;
     }
@@ -0,0 +1,89 @@
+//! This module exposes observers which are notified of everything happening at a table, i. e. to
+//! compose logging, statistics and UI updates without driving the rounds.
+use crate::actions::Action;
+use crate::player::{Player, Seats};
+use crate::state::TransparentState;
+use crate::summary::RoundSummary;
+
+/// Receives the events at a table it is registered on, see `Table::with_observer`.
+///
+/// All functions are called with the state right after the event. By default events are ignored.
+pub trait TableObserver {
+    /// This function gets called for every action taken in the rounds played at the table.
+    fn on_action(&mut self, _action: &Action, _state: &TransparentState) {}
+
+    /// This function gets called once a round ended, right after its last action.
+    fn on_round_end(&mut self, _state: &TransparentState) {}
+
+    /// This function gets called when the player at the given position lost all the chips and
+    /// left the table, see `Player::bust`.
+    fn on_bust(&mut self, _position: usize, _state: &TransparentState) {}
+}
+
+/// Notifies the given observers of an action taken in a round.
+pub(crate) fn notify(
+    observers: &mut [Box<dyn TableObserver>],
+    action: &Action,
+    state: &TransparentState,
+    round_ended: bool,
+) {
+    for observer in observers.iter_mut() {
+        observer.on_action(action, state);
+        if round_ended {
+            observer.on_round_end(state);
+        }
+    }
+}
+
+/// Lets the given players, indexed by position, observe an action taken in a round.
+///
+/// Players sitting out observe the round as well, players who busted or cashed out do not.
+pub(crate) fn notify_players<P: Player>(
+    players: &mut Seats<P>,
+    action: &Action,
+    state: &TransparentState,
+    round_ended: bool,
+) {
+    for (position, player) in players.iter_mut() {
+        if state.seated[position] && action.is_visible_to(position) {
+            player.observe(action);
+        }
+    }
+    if round_ended {
+        let summary = RoundSummary::new(state);
+        for (position, player) in players.iter_mut() {
+            if state.seated[position] {
+                player.round_finished(&summary);
+            }
+        }
+    }
+}
+
+/// Lets the player at the given position bust, i. e. the player leaves the seat after losing all
+/// the chips, and notifies the given observers.
+pub(crate) fn bust<P: Player>(
+    player: &mut P,
+    observers: &mut [Box<dyn TableObserver>],
+    position: usize,
+    state: &mut TransparentState,
+) {
+    state.empty_seat(position);
+    player.bust();
+    for observer in observers.iter_mut() {
+        observer.on_bust(position, state);
+    }
+}
+
+/// Lets all the players, indexed by position, who lost their chips in the round which just ended
+/// bust, see `bust`.
+pub(crate) fn bust_players<P: Player>(
+    players: &mut Seats<P>,
+    observers: &mut [Box<dyn TableObserver>],
+    state: &mut TransparentState,
+) {
+    for (position, player) in players.iter_mut() {
+        if state.seated[position] && state.player_stacks[position] == 0 {
+            bust(player, observers, position, state);
+        }
+    }
+}
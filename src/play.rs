@@ -1,23 +1,40 @@
-use crate::actions::Action;
+use crate::actions::{Action, PlayerAction};
 use crate::deck::{Card, Deck};
-use crate::player::Player;
+use crate::observer::{self, TableObserver};
+use crate::player::{Player, Seats};
+use crate::replay::{self, Divergence};
 use crate::state::{BetRoundState, CheckpointState, TransparentState};
+use crate::ChipCount;
 use std::ops::DerefMut;
 
+/// The maximum number of times the rest of the board may be run, see `Player::run_count`.
+pub(crate) const MAX_RUNS: usize = 3;
+
 /// This enum represents the current stage of the round.
 /// It is used for the `Round` structure to hold state information
 #[derive(Debug, Clone)]
 enum RoundIteratorStage {
     /// The round is about to start
     Init,
+    /// The seat at the given position is about to be checked for players sitting out or
+    /// returning after sitting out.
+    Seats(usize),
     /// The player at the given position is about to receive its cards
     DealHand(usize),
+    /// The player at the given index of the active players is about to pay the ante.
+    /// Note that we will deal cards before the antes.
+    Ante(usize),
     /// The small blind is about to be placed.
     /// Note that we will deal cards before the blinds.
     SmallBlind,
     /// The big blind is about to be placed.
     /// Note that we will deal cards before the blinds.
     BigBlind,
+    /// The player at the given index of the active players is about to post missed blinds, see
+    /// `MissedBlinds`.
+    MissedBlinds(usize),
+    /// A straddle is about to be offered, see `Straddle`.
+    Straddle,
     /// This represents the state after the blinds have been dealt.
     /// Eventually it ends when either no player is remaining (skips ahead to distribute the pot and sets the stage to `PastEnd`) or
     /// the flop is dealt, which progresses the state to `PostFlop`.
@@ -28,6 +45,15 @@ enum RoundIteratorStage {
     PostTurn(BetRoundState),
     /// Analogous to `PostBlind`, this represents the state after the river has been dealt.
     PostRiver(BetRoundState),
+    /// All the remaining players are all-in and agreed to run the rest of the board `runs` times.
+    /// The board of the run with index `run` is dealt, followed by the wins of this run.
+    /// The first `common` cards of the board are shared by all runs.
+    RunBoard {
+        run: usize,
+        runs: usize,
+        common: usize,
+        side_pots: Vec<(ChipCount, Vec<usize>)>,
+    },
     /// The past-end stage, indicating that this round is finished. This stage will loop indefinitely.
     PastEnd,
 }
@@ -37,40 +63,69 @@ enum RoundIteratorStage {
 ///
 /// This iterator object supports freezing for later replay (fast-forward play).
 pub struct Round<'a, P: Player, T: DerefMut<Target = TransparentState>> {
-    players: &'a mut [P],
+    players: Seats<'a, P>,
+    observers: &'a mut [Box<dyn TableObserver>],
+    // whether players bust at the end of the round, see `with_busts`
+    busts: bool,
     transparent_state: T,
     next_cards: Vec<Card>,
     iterator_stage: RoundIteratorStage,
+    runs_offered: bool,
+    // whether decisions are taken outside of the round, see `decide`
+    external_decisions: bool,
 }
 
 /// This is a checkpoint in a gameplay.
 ///
 /// It is independent of players and tables.
 /// You can use it to replay any round at any given time.
+#[derive(Debug, Clone)]
 pub struct RoundCheckpoint {
     transparent_state: TransparentState,
     next_cards: Vec<Card>,
     iterator_stage: RoundIteratorStage,
+    runs_offered: bool,
 }
 
 impl<'a, P: Player, T: DerefMut<Target = TransparentState>> Round<'a, P, T> {
-    pub(crate) fn new(players: &'a mut [P], mut transparent_state: T, mut deck: impl Deck) -> Self {
+    pub(crate) fn new(
+        players: &'a mut [Option<P>],
+        mut transparent_state: T,
+        mut deck: impl Deck,
+    ) -> Self {
         transparent_state.reset();
         transparent_state.prepare_hands(&mut deck);
 
         // we pre-emptively "fill" the board in order to make serialization less heavy
-        let mut next_cards = Vec::with_capacity(5);
-        for _ in 0..5 {
-            next_cards.push(deck.deal().expect("Deck should contain enough cards"));
+        // if the deck allows, we reserve the cards required to run the board multiple times as well
+        let mut next_cards = Vec::with_capacity(5 * MAX_RUNS);
+        while let Some(card) = deck.deal() {
+            next_cards.push(card);
+            if next_cards.len() == 5 * MAX_RUNS {
+                break;
+            }
         }
+        let board_cards = if transparent_state.double_board {
+            10
+        } else {
+            5
+        };
+        assert!(
+            next_cards.len() >= board_cards,
+            "Deck should contain enough cards"
+        );
         // we will want to preserve order, just for consistency reasons (since we will be popping from back to front)
         next_cards.reverse();
 
         Self {
-            players,
+            players: Seats::new(players),
+            observers: &mut [],
+            busts: false,
             transparent_state,
             next_cards,
             iterator_stage: RoundIteratorStage::Init,
+            runs_offered: false,
+            external_decisions: false,
         }
     }
 
@@ -80,62 +135,278 @@ impl<'a, P: Player, T: DerefMut<Target = TransparentState>> Round<'a, P, T> {
             transparent_state: (*self.transparent_state).clone(),
             next_cards: self.next_cards.clone(),
             iterator_stage: self.iterator_stage.clone(),
+            runs_offered: self.runs_offered,
         }
     }
 
     /// Inspect the underlying `TransparentState` before stepping to the next action.
     pub fn inspect_state(&self) -> &'_ TransparentState {
-        &*self.transparent_state
+        &self.transparent_state
+    }
+
+    /// Notifies the given observers of all the following actions.
+    pub(crate) fn with_observers(mut self, observers: &'a mut [Box<dyn TableObserver>]) -> Self {
+        self.observers = observers;
+        self
+    }
+
+    /// Whether the players who lost all their chips bust once the round ended, see
+    /// `Player::bust`.
+    pub(crate) fn with_busts(mut self, busts: bool) -> Self {
+        self.busts = busts;
+        self
     }
 
     fn end_round(&mut self) -> Action {
         self.iterator_stage = RoundIteratorStage::PastEnd;
         self.transparent_state.end_round()
     }
+
+    /// Offers the remaining players to run the rest of the board multiple times once they are
+    /// all-in, see `Player::run_count`.
+    ///
+    /// Returns `true` if they agreed to run it more than once. In this case the round continues
+    /// with the first run.
+    fn offer_runs(&mut self) -> bool {
+        if self.runs_offered
+            || self.transparent_state.double_board
+            || !self.transparent_state.all_in()
+        {
+            return false;
+        }
+        self.runs_offered = true;
+
+        let common = self.transparent_state.board.all_cards().len();
+        let max_runs = std::cmp::min(MAX_RUNS, self.next_cards.len() / (5 - common));
+        if max_runs < 2 {
+            return false;
+        }
+
+        let state = &*self.transparent_state;
+        let mut runs = max_runs;
+        for &pos in state.player_positions.iter() {
+            runs = std::cmp::min(runs, self.players[pos].run_count(state, max_runs));
+        }
+        if runs < 2 {
+            return false;
+        }
+
+        self.iterator_stage = RoundIteratorStage::RunBoard {
+            run: 0,
+            runs,
+            common,
+            side_pots: Vec::new(),
+        };
+        true
+    }
+
+    /// Returns the position of the player who decides in the next step, or `None` if the next
+    /// step does not require a decision.
+    ///
+    /// Forced bets and the offers to straddle or to run the board multiple times are not
+    /// considered decisions.
+    pub(crate) fn next_to_act(&self) -> Option<usize> {
+        let state = &*self.transparent_state;
+        if state.double_board
+            && state.second_board.all_cards().len() < state.board.all_cards().len()
+        {
+            return None;
+        }
+        match &self.iterator_stage {
+            RoundIteratorStage::PostBlind(i)
+            | RoundIteratorStage::PostFlop(i)
+            | RoundIteratorStage::PostTurn(i)
+            | RoundIteratorStage::PostRiver(i) => state.next_to_act(i),
+            _ => None,
+        }
+    }
+
+    /// Lets the round pause whenever a player has to decide instead of asking the player.
+    ///
+    /// A paused round returns `None` from `next` while `next_to_act` returns the player who has to
+    /// decide. The round continues once the decision is taken using `decide`.
+    pub(crate) fn with_external_decisions(mut self) -> Self {
+        self.external_decisions = true;
+        self
+    }
+
+    /// Takes the given decision for the player returned by `next_to_act` in the next step instead
+    /// of asking the player.
+    pub(crate) fn decide(&mut self, decision: PlayerAction) {
+        match &mut self.iterator_stage {
+            RoundIteratorStage::PostBlind(i)
+            | RoundIteratorStage::PostFlop(i)
+            | RoundIteratorStage::PostTurn(i)
+            | RoundIteratorStage::PostRiver(i) => i.decide(decision),
+            _ => panic!("Decisions should only be taken in bet rounds"),
+        }
+    }
+
+    /// Takes the given decision for the player returned by `next_to_act` like `decide`, the
+    /// decision is recorded after an `Action::Timeout` of the player.
+    pub(crate) fn decide_on_timeout(&mut self, decision: PlayerAction) {
+        match &mut self.iterator_stage {
+            RoundIteratorStage::PostBlind(i)
+            | RoundIteratorStage::PostFlop(i)
+            | RoundIteratorStage::PostTurn(i)
+            | RoundIteratorStage::PostRiver(i) => i.decide_on_timeout(decision),
+            _ => panic!("Decisions should only be taken in bet rounds"),
+        }
+    }
+
+    /// Borrows the players and the state at the same time, e. g. to ask a player for a decision.
+    pub(crate) fn split(&mut self) -> (&mut Seats<'a, P>, &TransparentState) {
+        (&mut self.players, &self.transparent_state)
+    }
+
+    /// The stage following the deal and the antes. Games without blinds continue with the
+    /// pre-flop betting right away, bomb pots skip the blinds and the pre-flop betting.
+    fn blinds_stage(&self) -> RoundIteratorStage {
+        if self.transparent_state.bomb_pot.is_some() {
+            RoundIteratorStage::PostBlind(BetRoundState::skipped())
+        } else if self.transparent_state.blind_size == 0 {
+            RoundIteratorStage::PostBlind(self.transparent_state.init_pre_flop_action())
+        } else {
+            RoundIteratorStage::SmallBlind
+        }
+    }
+}
+
+impl RoundCheckpoint {
+    /// Create a checkpoint of a round which is about to start.
+    ///
+    /// The given state is expected to be prepared already, i. e. hands are dealt and the
+    /// dealer position is set. The given board cards will be dealt in order.
+    pub(crate) fn new(transparent_state: TransparentState, mut board: Vec<Card>) -> Self {
+        // cards are popped from back to front
+        board.reverse();
+        Self {
+            transparent_state,
+            next_cards: board,
+            iterator_stage: RoundIteratorStage::Init,
+            runs_offered: false,
+        }
+    }
+
+    /// Reconstruct the checkpoint of a recorded round right before the action at `index` was taken.
+    ///
+    /// The given `actions` are expected to describe a round from its start, i. e.
+    /// `TransparentState::actions` after the round was played. They are replayed up to `index`
+    /// (see `replay::replay`), which fails if the engine does not reproduce them.
+    /// If `index` exceeds the number of actions the checkpoint is taken after the last one.
+    ///
+    /// The checkpoint can be used to branch off alternative lines using `Table::replay_one_round`.
+    pub fn from_actions(actions: &[Action], index: usize) -> Result<Self, Divergence> {
+        replay::checkpoint_at(actions, index)
+    }
+
+    /// Inspect the `TransparentState` captured by this checkpoint.
+    pub fn inspect_state(&self) -> &'_ TransparentState {
+        &self.transparent_state
+    }
 }
 
 impl<'a, P: Player> Round<'a, P, CheckpointState> {
-    pub(crate) fn from_checkpoint(players: &'a mut [P], cp: RoundCheckpoint) -> Self {
+    pub(crate) fn from_checkpoint(players: &'a mut [Option<P>], cp: RoundCheckpoint) -> Self {
         Self {
-            players,
+            players: Seats::new(players),
+            observers: &mut [],
+            busts: false,
             transparent_state: CheckpointState::new(cp.transparent_state),
             next_cards: cp.next_cards,
             iterator_stage: cp.iterator_stage,
+            runs_offered: cp.runs_offered,
+            external_decisions: false,
         }
     }
 }
 
-impl<'a, P: Player, T: DerefMut<Target = TransparentState>> Iterator for Round<'a, P, T> {
-    type Item = Action;
+impl<'a, P: Player, T: DerefMut<Target = TransparentState>> Round<'a, P, T> {
+    /// Progresses the state of the round one step ahead, see `next`.
+    fn step(&mut self) -> Option<Action> {
+        // each street of the second board is dealt right after the street of the first board
+        let state = &mut *self.transparent_state;
+        if state.double_board
+            && state.second_board.all_cards().len() < state.board.all_cards().len()
+        {
+            return Some(state.deal_second_board(&mut self.next_cards));
+        }
 
-    /// Progresses the state of the round one step ahead.
-    /// All actions taken so far are mirrored into the underlying `TransparentState`
-    fn next(&mut self) -> Option<Self::Item> {
         match &mut self.iterator_stage {
             RoundIteratorStage::Init => {
-                self.iterator_stage = RoundIteratorStage::DealHand(0);
+                self.iterator_stage = RoundIteratorStage::Seats(0);
                 Some(self.transparent_state.start_round())
             }
+            RoundIteratorStage::Seats(seat) => {
+                let mut seat = *seat;
+                while seat < self.transparent_state.num_players_total() {
+                    let action = self.transparent_state.seat_action(seat);
+                    seat += 1;
+                    if action.is_some() {
+                        self.iterator_stage = RoundIteratorStage::Seats(seat);
+                        return action;
+                    }
+                }
+                self.iterator_stage = RoundIteratorStage::DealHand(0);
+                self.step()
+            }
             RoundIteratorStage::DealHand(i) => {
                 let i = *i;
-                self.iterator_stage = if i + 1 >= self.transparent_state.num_players() {
-                    RoundIteratorStage::SmallBlind
-                } else {
+                self.iterator_stage = if i + 1 < self.transparent_state.num_players() {
                     RoundIteratorStage::DealHand(i + 1)
+                } else if self.transparent_state.ante_size() > 0 {
+                    RoundIteratorStage::Ante(0)
+                } else {
+                    self.blinds_stage()
                 };
                 Some(self.transparent_state.deal_hand(i))
             }
+            RoundIteratorStage::Ante(i) => {
+                let i = *i;
+                self.iterator_stage = if i + 1 < self.transparent_state.num_players() {
+                    RoundIteratorStage::Ante(i + 1)
+                } else {
+                    self.blinds_stage()
+                };
+                Some(self.transparent_state.apply_ante(i, &mut self.players))
+            }
             RoundIteratorStage::SmallBlind => {
                 self.iterator_stage = RoundIteratorStage::BigBlind;
                 Some(self.transparent_state.apply_small_blind(&mut self.players))
             }
             RoundIteratorStage::BigBlind => {
+                self.iterator_stage = RoundIteratorStage::MissedBlinds(2);
+                Some(self.transparent_state.apply_big_blind(&mut self.players))
+            }
+            RoundIteratorStage::MissedBlinds(i) => {
+                let mut i = *i;
+                while i < self.transparent_state.num_players() {
+                    let action = self
+                        .transparent_state
+                        .apply_missed_blinds(i, &mut self.players);
+                    i += 1;
+                    if action.is_some() {
+                        self.iterator_stage = RoundIteratorStage::MissedBlinds(i);
+                        return action;
+                    }
+                }
+                self.iterator_stage = RoundIteratorStage::Straddle;
+                self.step()
+            }
+            RoundIteratorStage::Straddle => {
+                let action = self.transparent_state.apply_straddle(&mut self.players);
                 self.iterator_stage =
                     RoundIteratorStage::PostBlind(self.transparent_state.init_pre_flop_action());
-                Some(self.transparent_state.apply_big_blind(&mut self.players))
+                action.or_else(|| self.step())
             }
             RoundIteratorStage::PostBlind(i) => {
                 while !i.done() {
+                    if self.external_decisions
+                        && !i.decided()
+                        && self.transparent_state.next_to_act(i).is_some()
+                    {
+                        return None;
+                    }
                     let action = self.transparent_state.step_bet_round(i, &mut self.players);
                     if action.is_some() {
                         return action;
@@ -144,6 +415,8 @@ impl<'a, P: Player, T: DerefMut<Target = TransparentState>> Iterator for Round<'
 
                 if self.transparent_state.num_players() == 1 {
                     Some(self.end_round())
+                } else if self.offer_runs() {
+                    self.step()
                 } else {
                     // deal flop
                     self.iterator_stage = RoundIteratorStage::PostFlop(
@@ -158,6 +431,12 @@ impl<'a, P: Player, T: DerefMut<Target = TransparentState>> Iterator for Round<'
             }
             RoundIteratorStage::PostFlop(i) => {
                 while !i.done() {
+                    if self.external_decisions
+                        && !i.decided()
+                        && self.transparent_state.next_to_act(i).is_some()
+                    {
+                        return None;
+                    }
                     let action = self.transparent_state.step_bet_round(i, &mut self.players);
                     if action.is_some() {
                         return action;
@@ -166,6 +445,8 @@ impl<'a, P: Player, T: DerefMut<Target = TransparentState>> Iterator for Round<'
 
                 if self.transparent_state.num_players() == 1 {
                     Some(self.end_round())
+                } else if self.offer_runs() {
+                    self.step()
                 } else {
                     // deal turn
                     self.iterator_stage = RoundIteratorStage::PostTurn(
@@ -179,6 +460,12 @@ impl<'a, P: Player, T: DerefMut<Target = TransparentState>> Iterator for Round<'
             }
             RoundIteratorStage::PostTurn(i) => {
                 while !i.done() {
+                    if self.external_decisions
+                        && !i.decided()
+                        && self.transparent_state.next_to_act(i).is_some()
+                    {
+                        return None;
+                    }
                     let action = self.transparent_state.step_bet_round(i, &mut self.players);
                     if action.is_some() {
                         return action;
@@ -187,6 +474,8 @@ impl<'a, P: Player, T: DerefMut<Target = TransparentState>> Iterator for Round<'
 
                 if self.transparent_state.num_players() == 1 {
                     Some(self.end_round())
+                } else if self.offer_runs() {
+                    self.step()
                 } else {
                     // deal river
                     self.iterator_stage = RoundIteratorStage::PostRiver(
@@ -200,6 +489,12 @@ impl<'a, P: Player, T: DerefMut<Target = TransparentState>> Iterator for Round<'
             }
             RoundIteratorStage::PostRiver(i) => {
                 while !i.done() {
+                    if self.external_decisions
+                        && !i.decided()
+                        && self.transparent_state.next_to_act(i).is_some()
+                    {
+                        return None;
+                    }
                     let action = self.transparent_state.step_bet_round(i, &mut self.players);
                     if action.is_some() {
                         return action;
@@ -208,7 +503,63 @@ impl<'a, P: Player, T: DerefMut<Target = TransparentState>> Iterator for Round<'
 
                 Some(self.end_round())
             }
+            RoundIteratorStage::RunBoard {
+                run,
+                runs,
+                common,
+                side_pots,
+            } => {
+                if self.transparent_state.board.all_cards().len() < 5 {
+                    return Some(self.transparent_state.deal_run(*run, &mut self.next_cards));
+                }
+
+                // the pot is split once the first run is complete
+                if *run == 0 {
+                    *side_pots = self.transparent_state.take_side_pots();
+                }
+                let action = self.transparent_state.award_run(*run, *runs, side_pots);
+                if *run + 1 < *runs {
+                    let board = self.transparent_state.board;
+                    self.transparent_state.run_boards.push(board);
+                    self.transparent_state.board.truncate(*common);
+                    *run += 1;
+                } else {
+                    self.iterator_stage = RoundIteratorStage::PastEnd;
+                }
+                Some(action)
+            }
             RoundIteratorStage::PastEnd => None,
         }
     }
 }
+
+impl<'a, P: Player, T: DerefMut<Target = TransparentState>> Iterator for Round<'a, P, T> {
+    type Item = Action;
+
+    /// Progresses the state of the round one step ahead.
+    /// All actions taken so far are mirrored into the underlying `TransparentState`
+    fn next(&mut self) -> Option<Self::Item> {
+        let action = self.step()?;
+        let round_ended = matches!(self.iterator_stage, RoundIteratorStage::PastEnd);
+        observer::notify_players(
+            &mut self.players,
+            &action,
+            &self.transparent_state,
+            round_ended,
+        );
+        observer::notify(
+            self.observers,
+            &action,
+            &self.transparent_state,
+            round_ended,
+        );
+        if round_ended && self.busts {
+            observer::bust_players(
+                &mut self.players,
+                self.observers,
+                &mut self.transparent_state,
+            );
+        }
+        Some(action)
+    }
+}
@@ -1,7 +1,9 @@
 //! This module exposes the main player trait.
-use crate::actions::PlayerAction;
+use crate::actions::{Action, PlayerAction};
+use crate::summary::RoundSummary;
 use crate::ChipCount;
 use crate::TransparentState;
+use std::ops::{Index, IndexMut};
 
 /// A trait to be implemented by anyone who is playing
 pub trait Player {
@@ -24,6 +26,99 @@ pub trait Player {
     /// action if this action allows it. See the documentation for `PlayerAction` for details.
     fn act(&mut self, state: &TransparentState, possible_actions: &[PlayerAction]) -> PlayerAction;
 
+    /// This function gets called when the player may post a straddle of the given size, see `Straddle`.
+    ///
+    /// The player straddles by returning `true`. By default the player does not straddle.
+    fn straddle(&mut self, _state: &TransparentState, _size: ChipCount) -> bool {
+        false
+    }
+
+    /// This function gets called when all the players remaining in the round are all-in before the river.
+    ///
+    /// The player may agree to run the rest of the board multiple times by returning a number of runs up to `max_runs`.
+    /// The board is run as many times as all the remaining players agree on, i.e. the minimum of all their answers.
+    /// Each pot is split evenly across the runs.
+    ///
+    /// By default the board is run once.
+    fn run_count(&mut self, _state: &TransparentState, _max_runs: usize) -> usize {
+        1
+    }
+
+    /// This function gets called between rounds of a cash game if the player lost all the chips,
+    /// see `Table::with_cash_game`.
+    ///
+    /// The player may buy in again by returning the number of chips to buy, which is limited to
+    /// the given range. Otherwise the player busts. By default the player does not rebuy.
+    fn rebuy(
+        &mut self,
+        _state: &TransparentState,
+        _min_buy_in: ChipCount,
+        _max_buy_in: ChipCount,
+    ) -> Option<ChipCount> {
+        None
+    }
+
+    /// This function gets called between rounds, the player sits out the next round by returning
+    /// `true`, see `Table::set_sitting_out`.
+    ///
+    /// The given flag tells whether the player currently sits out. By default the player keeps
+    /// sitting out or playing.
+    fn sit_out(&mut self, _state: &TransparentState, sitting_out: bool) -> bool {
+        sitting_out
+    }
+
+    /// This function gets called for every action taken in a round the player is seated in,
+    /// including the actions of the player itself. Players sitting out observe the round as well,
+    /// players who busted or cashed out do not.
+    ///
+    /// Private information is filtered, i. e. the player only observes the cards dealt face down
+    /// to itself. The hands revealed at showdown are not observed as actions, they are part of
+    /// the summary passed to `round_finished`. By default actions are ignored.
+    fn observe(&mut self, _action: &Action) {}
+
+    /// This function gets called once a round the player is seated in finished, right after the
+    /// last action was observed.
+    ///
+    /// The summary tells the outcome of the round, see `RoundSummary`. By default it is ignored.
+    fn round_finished(&mut self, _summary: &RoundSummary) {}
+
     /// This function gets called when the player lost all the chips and has to leave the table.
     fn bust(&mut self);
 }
+
+/// The players seated at a table, indexed by position, see `Table::seat_player`.
+///
+/// Empty seats are never dealt in, hence they are never asked to act.
+pub(crate) struct Seats<'a, P>(&'a mut [Option<P>]);
+
+impl<'a, P> Seats<'a, P> {
+    pub(crate) fn new(seats: &'a mut [Option<P>]) -> Self {
+        Self(seats)
+    }
+
+    /// The seated players along with their positions.
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = (usize, &mut P)> {
+        self.0
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(position, seat)| seat.as_mut().map(|player| (position, player)))
+    }
+}
+
+impl<P> Index<usize> for Seats<'_, P> {
+    type Output = P;
+
+    fn index(&self, position: usize) -> &P {
+        self.0[position]
+            .as_ref()
+            .expect("Empty seats should not be dealt in")
+    }
+}
+
+impl<P> IndexMut<usize> for Seats<'_, P> {
+    fn index_mut(&mut self, position: usize) -> &mut P {
+        self.0[position]
+            .as_mut()
+            .expect("Empty seats should not be dealt in")
+    }
+}
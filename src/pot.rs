@@ -13,6 +13,8 @@ pub struct Pot {
     bet_size: ChipCount,
     bet_size_round: ChipCount,
     last_raise_amount: ChipCount,
+    // chips which do not count as bets of any player, i. e. dead blinds
+    dead_chips: ChipCount,
 }
 
 impl Pot {
@@ -23,6 +25,7 @@ impl Pot {
             bet_size: 0,
             bet_size_round: 0,
             last_raise_amount: 0,
+            dead_chips: 0,
         }
     }
 
@@ -34,6 +37,13 @@ impl Pot {
         self.bet_size = 0;
         self.bet_size_round = 0;
         self.last_raise_amount = 0;
+        self.dead_chips = 0;
+    }
+
+    /// Place the given amount of dead chips into the pot, i. e. chips which do not count as a
+    /// bet of any player. Dead chips go to the main pot.
+    pub(crate) fn place_dead_chips(&mut self, amount: ChipCount) {
+        self.dead_chips += amount;
     }
 
     /// Place the given amount of chips from player located at `player_position` into the pot.
@@ -85,7 +95,7 @@ impl Pot {
         player_positions.sort_by_key(|&pos| self.player_bets[pos]);
 
         let mut n_receivers = player_positions.len() as u32;
-        let mut pot_size = 0;
+        let mut pot_size = std::mem::replace(&mut self.dead_chips, 0);
 
         let mut stacks = vec![0; self.player_bets.len()];
 
@@ -108,13 +118,69 @@ impl Pot {
         stacks
     }
 
+    /// Splits the pot into the main pot and the side pots which are contested by the players
+    /// located at `player_positions` and removes all the chips from the pot.
+    ///
+    /// Returns the size of each pot together with the positions of the players eligible to
+    /// win it, starting with the main pot. Chips which exceed the highest bet of any eligible
+    /// player are added to the last pot.
+    pub(crate) fn take_side_pots(
+        &mut self,
+        player_positions: &[usize],
+    ) -> Vec<(ChipCount, Vec<usize>)> {
+        if self.bet_size_round != 0 {
+            self.end_bet_round()
+        }
+
+        let mut levels: Vec<ChipCount> = player_positions
+            .iter()
+            .map(|&pos| self.player_bets[pos])
+            .collect();
+        levels.sort_unstable();
+        levels.dedup();
+
+        let mut side_pots: Vec<(ChipCount, Vec<usize>)> = Vec::new();
+        let mut previous_level = 0;
+        for level in levels {
+            let size = self
+                .player_bets
+                .iter()
+                .map(|&bet| bet.min(level) - bet.min(previous_level))
+                .sum();
+            let eligible = player_positions
+                .iter()
+                .copied()
+                .filter(|&pos| self.player_bets[pos] >= level)
+                .collect();
+            side_pots.push((size, eligible));
+            previous_level = level;
+        }
+
+        let rest: ChipCount = self
+            .player_bets
+            .iter()
+            .map(|&bet| bet - bet.min(previous_level))
+            .sum();
+        if let Some((size, _)) = side_pots.last_mut() {
+            *size += rest;
+        }
+        if let Some((size, _)) = side_pots.first_mut() {
+            *size += std::mem::replace(&mut self.dead_chips, 0);
+        }
+
+        for bet in self.player_bets.iter_mut() {
+            *bet = 0;
+        }
+        side_pots
+    }
+
     pub(crate) fn last_raise_amount(&self) -> ChipCount {
         self.last_raise_amount
     }
 
     /// Calculate the total number of chips contained in the pot
     pub fn total_size(&self) -> ChipCount {
-        self.player_bets.iter().sum()
+        self.player_bets.iter().sum::<ChipCount>() + self.dead_chips
     }
 
     /// Returns the total number of chips each player has/had to put into the pot to stay in it.
@@ -136,6 +202,7 @@ impl Pot {
             .iter()
             .map(|x| std::cmp::min(eff_bet_size, *x))
             .sum::<u32>()
+            + self.dead_chips
             + bet_size
     }
 
@@ -281,7 +348,7 @@ mod tests {
         let stacks: Vec<_> = pot
             .distribute(&[2])
             .into_iter()
-            .zip(pot.distribute(&[1]).into_iter())
+            .zip(pot.distribute(&[1]))
             .map(|(x, y)| x + y)
             .collect();
         assert_eq!(stacks, [0, 8, 33]);
@@ -298,4 +365,21 @@ mod tests {
         pot.place_chips(2, 11);
         assert_eq!(pot.last_raise_amount(), 6);
     }
+
+    #[test]
+    fn test_take_side_pots() {
+        let mut pot = Pot::new(4);
+        pot.place_chips(0, 10);
+        pot.place_chips(1, 30);
+        pot.place_chips(2, 50);
+        pot.place_chips(3, 50);
+
+        // player 3 folded
+        let side_pots = pot.take_side_pots(&[0, 1, 2]);
+        assert_eq!(
+            side_pots,
+            vec![(40, vec![0, 1, 2]), (60, vec![1, 2]), (40, vec![2])]
+        );
+        assert!(pot.is_empty());
+    }
 }
@@ -0,0 +1,523 @@
+//! This module provides means to replay rounds from a recorded stream of actions.
+//!
+//! Replaying a round verifies that the engine still produces exactly the recorded actions,
+//! which is useful to make sure rule changes do not alter the outcome of past rounds.
+//!
+//! ### Example:
+//! ```rust
+//! use poppy::actions::Action;
+//! use poppy::deck::CardCollection;
+//! use poppy::replay::replay;
+//! use poppy::{BettingStructure, GameVariant};
+//! use std::convert::TryFrom;
+//!
+//! let cards = CardCollection::try_from("AhAdKsKc7d2c").unwrap();
+//! let actions = vec![
+//!     Action::StartRound {
+//!         id: 1,
+//!         big_blind: 2,
+//!         small_blind: 1,
+//!         dealer_position: 0,
+//!         player_stacks: vec![100, 100, 100],
+//!         betting_structure: BettingStructure::NoLimit,
+//!         variant: GameVariant::Holdem,
+//!         ante: 0,
+//!         bomb_pot: None,
+//!     },
+//!     Action::DealHand(1, vec![cards[0], cards[1]]),
+//!     Action::DealHand(2, vec![cards[2], cards[3]]),
+//!     Action::DealHand(0, vec![cards[4], cards[5]]),
+//!     Action::Blind(1, 1),
+//!     Action::Blind(2, 2),
+//!     Action::Fold(0),
+//!     Action::Fold(1),
+//!     Action::Win(vec![(2, 3)]),
+//! ];
+//!
+//! let state = replay(&actions).unwrap();
+//! assert_eq!(state.player_stacks, [100, 99, 101]);
+//!
+//! let mut diverging = actions.clone();
+//! diverging[8] = Action::Win(vec![(2, 4)]);
+//! assert_eq!(replay(&diverging).unwrap_err().index, 8);
+//! ```
+use crate::actions::{Action, PlayerAction};
+use crate::deck::Card;
+use crate::play::{Round, RoundCheckpoint, MAX_RUNS};
+use crate::player::Player;
+use crate::state::{CheckpointState, TransparentState};
+use crate::{ChipCount, GameVariant, Straddle};
+use std::collections::VecDeque;
+use std::fmt;
+
+/// A player which takes pre-recorded decisions.
+///
+/// If a recorded decision is not possible at the time it is requested, the player checks or
+/// folds instead. The round will diverge from the recording in this case.
+#[derive(Debug, Clone)]
+pub struct ScriptedPlayer {
+    // the decisions and whether the player timed out taking them
+    decisions: VecDeque<(PlayerAction, bool)>,
+    runs: usize,
+    straddles: bool,
+}
+
+impl ScriptedPlayer {
+    /// Create a player taking all the decisions of the player at `position` in the given actions.
+    pub fn from_actions(actions: &[Action], position: usize) -> Self {
+        let mut timed_out = false;
+        let decisions = actions
+            .iter()
+            .filter_map(|action| {
+                let decision = match *action {
+                    Action::Timeout(pos) if pos == position => {
+                        timed_out = true;
+                        None
+                    }
+                    Action::Blind(pos, c) | Action::MissedBlinds(pos, c) if pos == position => {
+                        Some(PlayerAction::Blind(c))
+                    }
+                    Action::Ante(pos, c) if pos == position => Some(PlayerAction::Ante(c)),
+                    Action::BringIn(pos, c) if pos == position => Some(PlayerAction::BringIn(c)),
+                    Action::Check(pos) if pos == position => Some(PlayerAction::Check),
+                    Action::Call(pos, c) if pos == position => Some(PlayerAction::Call(c)),
+                    Action::Raise(pos, c) if pos == position => Some(PlayerAction::Raise(c)),
+                    Action::AllIn(pos, c) if pos == position => Some(PlayerAction::AllIn(c)),
+                    Action::Bet(pos, c) if pos == position => Some(PlayerAction::Bet(c)),
+                    Action::Fold(pos) if pos == position => Some(PlayerAction::Fold),
+                    _ => None,
+                };
+                decision.map(|decision| (decision, std::mem::take(&mut timed_out)))
+            })
+            .collect();
+        // the player agrees to run the board as often as it was run in the recording
+        let runs = actions
+            .iter()
+            .filter(
+                |action| matches!(action, Action::Run(_, win) if matches!(**win, Action::Win(_))),
+            )
+            .count();
+
+        let straddles = actions
+            .iter()
+            .any(|action| matches!(*action, Action::Straddle(pos, _) if pos == position));
+
+        Self {
+            decisions,
+            runs: std::cmp::max(runs, 1),
+            straddles,
+        }
+    }
+
+    /// Takes the next recorded decision, returns whether the player timed out taking it as well.
+    fn decide(&mut self, possible_actions: &[PlayerAction]) -> (PlayerAction, bool) {
+        match self.decisions.pop_front() {
+            Some((decision, timed_out)) if decision.is_allowed(possible_actions) => {
+                (decision, timed_out)
+            }
+            // if the recorded decision is not possible the replay diverged. We just try to make
+            // progress in the least intrusive way, the divergence is reported by the caller.
+            _ if possible_actions.contains(&PlayerAction::Check) => (PlayerAction::Check, false),
+            _ => (PlayerAction::Fold, false),
+        }
+    }
+}
+
+impl Player for ScriptedPlayer {
+    fn init(&mut self, _position: usize, _initial_stack: ChipCount) {}
+
+    fn act(
+        &mut self,
+        _state: &TransparentState,
+        possible_actions: &[PlayerAction],
+    ) -> PlayerAction {
+        self.decide(possible_actions).0
+    }
+
+    fn straddle(&mut self, _state: &TransparentState, _size: ChipCount) -> bool {
+        self.straddles
+    }
+
+    fn run_count(&mut self, _state: &TransparentState, _max_runs: usize) -> usize {
+        self.runs
+    }
+
+    fn bust(&mut self) {}
+}
+
+/// The first point at which a replayed round differs from the recorded actions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// The index of the first action which differs.
+    pub index: usize,
+    /// The recorded action, `None` if the replay produced more actions than recorded.
+    pub expected: Option<Box<Action>>,
+    /// The replayed action, `None` if the replay finished early.
+    pub actual: Option<Box<Action>>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Action {} differs: expected {:?}, got {:?}",
+            self.index, self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for Divergence {}
+
+/// Returns all the cards of the deck of the given variant which are not contained in `known_cards`.
+pub(crate) fn unused_cards(
+    variant: GameVariant,
+    known_cards: &[Card],
+) -> impl Iterator<Item = Card> {
+    variant
+        .deck()
+        .iter()
+        .copied()
+        .filter(|c| !known_cards.contains(c))
+        .collect::<Vec<Card>>()
+        .into_iter()
+}
+
+/// Returns the board cards dealt by the given action, including the cards dealt in any run.
+fn board_cards(action: &Action) -> &[Card] {
+    match action {
+        Action::DealFlop(cards) => cards,
+        Action::DealTurn(card) | Action::DealRiver(card) => std::slice::from_ref(card),
+        Action::Run(_, action) | Action::SecondBoard(action) => board_cards(action),
+        _ => &[],
+    }
+}
+
+/// Reconstructs the checkpoint at the start of the round described by the given actions.
+///
+/// Cards which are not known from the actions are substituted with arbitrary cards which are
+/// not in play otherwise.
+///
+/// Returns `None` if the actions do not start with `Action::StartRound` or describe a round of
+/// a stud game, which cannot be replayed.
+pub fn initial_checkpoint(actions: &[Action]) -> Option<RoundCheckpoint> {
+    let mut state = match actions.first() {
+        Some(Action::StartRound { variant, .. }) if variant.is_stud() => return None,
+        Some(Action::StartRound {
+            id,
+            small_blind,
+            dealer_position,
+            player_stacks,
+            betting_structure,
+            variant,
+            ante,
+            bomb_pot,
+            ..
+        }) => {
+            let mut state =
+                TransparentState::new(*small_blind, *dealer_position, player_stacks.clone());
+            state.id = *id;
+            state.betting_structure = *betting_structure;
+            state.variant = *variant;
+            state.ante = *ante;
+            state.bomb_pot = *bomb_pot;
+            state
+        }
+        _ => return None,
+    };
+
+    state.double_board = actions
+        .iter()
+        .any(|action| matches!(action, Action::SecondBoard(_)));
+    // only posted straddles are recorded, which suffices as declined straddles do not alter the round
+    state.straddle = actions.iter().find_map(|action| match *action {
+        Action::Straddle(pos, _) if pos == state.dealer_position => Some(Straddle::Button),
+        Action::Straddle(..) => Some(Straddle::UnderTheGun),
+        _ => None,
+    });
+
+    let mut known_cards = Vec::new();
+    let mut dealt = vec![false; state.num_players_total()];
+    let mut board = Vec::new();
+    for action in actions.iter() {
+        match action {
+            Action::DealHand(pos, cards) => {
+                state.hands[*pos] = cards.clone();
+                dealt[*pos] = true;
+                known_cards.extend_from_slice(cards);
+            }
+            Action::SitOut(pos) => state.sitting_out[*pos] = true,
+            Action::Return(pos) => state.returning[*pos] = true,
+            Action::MissedBlinds(pos, _) => state.owes_blinds[*pos] = true,
+            _ => board.extend_from_slice(board_cards(action)),
+        }
+    }
+    known_cards.extend_from_slice(&board);
+
+    // players who were not dealt in occupied empty seats or sat out
+    if dealt.iter().any(|&dealt| dealt) {
+        state.player_positions.retain(|&pos| dealt[pos]);
+        for (seated, (&dealt, &sitting_out)) in state
+            .seated
+            .iter_mut()
+            .zip(dealt.iter().zip(state.sitting_out.iter()))
+        {
+            *seated = dealt || sitting_out;
+        }
+    }
+
+    let mut unused_cards = unused_cards(state.variant, &known_cards);
+    for (hand, dealt) in state.hands.iter_mut().zip(dealt) {
+        if !dealt {
+            *hand = (&mut unused_cards)
+                .take(state.variant.hole_cards())
+                .collect();
+        }
+    }
+    while board.len() < 5 {
+        board.push(unused_cards.next().unwrap());
+    }
+    // reserve the cards to run the board multiple times if possible
+    board.extend(unused_cards.take(5 * MAX_RUNS - board.len()));
+
+    Some(RoundCheckpoint::new(state, board))
+}
+
+/// Replays the round described by the given actions and verifies that the engine produces
+/// exactly the recorded actions.
+///
+/// Wins are compared regardless of the order in which the players are listed.
+///
+/// The deck is reconstructed from the dealt cards and every player takes the decisions recorded
+/// for its position. If the actions do not start with `Action::StartRound` or describe a round
+/// of a stud game the divergence is reported at index 0.
+///
+/// Returns the state after the round finished.
+pub fn replay(actions: &[Action]) -> Result<TransparentState, Divergence> {
+    let mut players = scripted_players(actions)?;
+    let round = replay_until(&mut players, actions, actions.len() + 1)?;
+    Ok(round.inspect_state().clone())
+}
+
+/// Reconstructs the checkpoint of the recorded round right before the action at `index` was
+/// taken, see `RoundCheckpoint::from_actions`.
+pub(crate) fn checkpoint_at(
+    actions: &[Action],
+    index: usize,
+) -> Result<RoundCheckpoint, Divergence> {
+    let mut players = scripted_players(actions)?;
+    let round = replay_until(&mut players, actions, index.min(actions.len()))?;
+    Ok(round.create_checkpoint())
+}
+
+fn scripted_players(actions: &[Action]) -> Result<Vec<Option<ScriptedPlayer>>, Divergence> {
+    match actions.first() {
+        Some(Action::StartRound {
+            player_stacks,
+            variant,
+            ..
+        }) if !variant.is_stud() => Ok((0..player_stacks.len())
+            .map(|pos| Some(ScriptedPlayer::from_actions(actions, pos)))
+            .collect()),
+        first => Err(Divergence {
+            index: 0,
+            expected: first.cloned().map(Box::new),
+            actual: None,
+        }),
+    }
+}
+
+/// Replays the first `count` actions and verifies them.
+///
+/// `count` may exceed the number of actions by one in order to verify that the round ends.
+fn replay_until<'a>(
+    players: &'a mut [Option<ScriptedPlayer>],
+    actions: &[Action],
+    count: usize,
+) -> Result<Round<'a, ScriptedPlayer, CheckpointState>, Divergence> {
+    // `scripted_players` made sure that the round is started properly
+    let checkpoint = initial_checkpoint(actions).unwrap();
+    // the round pauses for decisions such that recorded timeouts are replayed as well
+    let mut round = Round::from_checkpoint(players, checkpoint).with_external_decisions();
+
+    for index in 0..count {
+        let expected = actions.get(index);
+        let actual = loop {
+            if let Some(action) = round.next() {
+                break Some(action);
+            }
+            let position = match round.next_to_act() {
+                Some(position) => position,
+                None => break None,
+            };
+            let (players, state) = round.split();
+            let possible_actions = state.possible_actions(position);
+            match players[position].decide(&possible_actions) {
+                (decision, false) => round.decide(decision),
+                (decision, true) => round.decide_on_timeout(decision),
+            }
+        };
+        let same = match (expected, &actual) {
+            (Some(Action::Win(expected)), Some(Action::Win(actual))) => {
+                let mut expected = expected.clone();
+                let mut actual = actual.clone();
+                expected.sort_unstable();
+                actual.sort_unstable();
+                expected == actual
+            }
+            (expected, actual) => expected == actual.as_ref(),
+        };
+        if !same {
+            return Err(Divergence {
+                index,
+                expected: expected.cloned().map(Box::new),
+                actual: actual.map(Box::new),
+            });
+        }
+    }
+
+    Ok(round)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::CardCollection;
+    use crate::mock::MockPlayer;
+    use crate::{BettingStructure, BlindPolicy, Table};
+
+    fn play_round() -> Vec<Action> {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Raise(10), PlayerAction::Fold]), // dealer
+            MockPlayer::new(vec![
+                PlayerAction::Blind(1),
+                PlayerAction::Call(9),
+                PlayerAction::Check,
+                PlayerAction::Call(2),
+                PlayerAction::Check,
+                PlayerAction::Check,
+            ]), // small
+            MockPlayer::new(vec![
+                PlayerAction::Blind(2),
+                PlayerAction::Call(8),
+                PlayerAction::Bet(2),
+                PlayerAction::Check,
+                PlayerAction::Check,
+            ]), // big
+            MockPlayer::new(vec![PlayerAction::Fold]),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+        let mut round = table.play_one_round(CardCollection::default()).unwrap();
+        for _ in &mut round {}
+        round.inspect_state().actions.clone()
+    }
+
+    #[test]
+    fn test_replay() {
+        let actions = play_round();
+        let state = replay(&actions).unwrap();
+        assert_eq!(state.actions, actions);
+        assert_eq!(state.pot.total_size(), 0);
+    }
+
+    #[test]
+    fn test_replay_timeout() {
+        let mut actions = play_round();
+        // the player left of the big blind folds after timing out
+        let index = actions
+            .iter()
+            .position(|action| *action == Action::Fold(3))
+            .unwrap();
+        actions.insert(index, Action::Timeout(3));
+
+        let state = replay(&actions).unwrap();
+        assert_eq!(state.actions, actions);
+    }
+
+    #[test]
+    fn test_replay_diverges() {
+        let mut actions = play_round();
+        // the small blind raises instead of calling
+        actions[9] = Action::Raise(1, 30);
+
+        let divergence = replay(&actions).unwrap_err();
+        assert_eq!(divergence.index, 10);
+        assert_eq!(divergence.expected, Some(Box::new(Action::Call(2, 8))));
+        assert_eq!(divergence.actual, Some(Box::new(Action::Fold(2))));
+    }
+
+    #[test]
+    fn test_checkpoint_at() {
+        let actions = play_round();
+        assert_eq!(actions[14], Action::Fold(0));
+
+        // the dealer calls the bet on the flop instead of folding
+        let players = vec![
+            MockPlayer::new(vec![
+                PlayerAction::Call(2),
+                PlayerAction::Check,
+                PlayerAction::Check,
+            ]), // dealer
+            MockPlayer::new(vec![
+                PlayerAction::Call(2),
+                PlayerAction::Check,
+                PlayerAction::Check,
+            ]), // small
+            MockPlayer::new(vec![PlayerAction::Check, PlayerAction::Check]), // big
+            MockPlayer::new(vec![]),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+
+        let checkpoint = RoundCheckpoint::from_actions(&actions, 14).unwrap();
+        assert_eq!(checkpoint.inspect_state().actions, actions[..14]);
+        let mut round = table.replay_one_round(checkpoint);
+        assert_eq!(round.next(), Some(Action::Call(0, 2)));
+        assert_eq!(round.next(), Some(Action::Call(1, 2)));
+        for _ in &mut round {}
+        assert_eq!(round.inspect_state().pot.total_size(), 0);
+        assert_eq!(
+            round
+                .inspect_state()
+                .player_stacks
+                .iter()
+                .sum::<ChipCount>(),
+            400
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_at_diverging_actions() {
+        let mut actions = play_round();
+        actions[9] = Action::Raise(1, 30);
+
+        assert!(RoundCheckpoint::from_actions(&actions, 9).is_ok());
+        assert_eq!(
+            RoundCheckpoint::from_actions(&actions, 14)
+                .unwrap_err()
+                .index,
+            10
+        );
+    }
+
+    #[test]
+    fn test_initial_checkpoint_substitutes_unknown_cards() {
+        let actions = [Action::StartRound {
+            id: 3,
+            big_blind: 2,
+            small_blind: 1,
+            dealer_position: 1,
+            player_stacks: vec![100, 50],
+            betting_structure: BettingStructure::NoLimit,
+            variant: GameVariant::Holdem,
+            ante: 0,
+            bomb_pot: None,
+        }];
+        let checkpoint = initial_checkpoint(&actions).unwrap();
+        let state = checkpoint.inspect_state();
+        let mut cards: Vec<Card> = state.hands.iter().flatten().copied().collect();
+        cards.sort();
+        cards.dedup();
+        assert_eq!(cards.len(), 4);
+        assert_eq!(state.id, 3);
+        assert_eq!(state.player_stacks, [100, 50]);
+    }
+}
@@ -1,10 +1,14 @@
 use crate::actions::{Action, PlayerAction};
+use crate::betting::{BettingStructure, MissedBlinds, Straddle};
 use crate::board::Board;
-use crate::deck::{Card, Deck};
+use crate::deck::card::Suit;
+use crate::deck::{Card, Deck, Rankable};
 use crate::player::Player;
 use crate::pot::Pot;
+use crate::variant::GameVariant;
 use crate::ChipCount;
-use std::ops::{Deref, DerefMut};
+use std::cmp::Ordering;
+use std::ops::{Deref, DerefMut, IndexMut};
 
 /// Structure to hold state information about one round of poker played which is visible to each player.
 #[derive(Debug, Clone)]
@@ -12,8 +16,25 @@ pub struct TransparentState {
     /// The current state of the board
     pub board: Board,
 
+    /// The current state of the second board in double board games, see `double_board`.
+    ///
+    /// The streets of the second board are dealt right after the streets of `board`. This is
+    /// empty if only one board is played.
+    pub second_board: Board,
+
+    /// The boards of the runs finished so far if the rest of the board is run multiple times.
+    ///
+    /// The board of the run in progress, or of the last run, is `board`. This is empty if the
+    /// board is run once.
+    pub run_boards: Vec<Board>,
+
     /// The cards each player is holding
-    pub hands: Vec<[Card; 2]>,
+    pub hands: Vec<Vec<Card>>,
+
+    /// The cards each player was dealt face up in stud games, which are visible to everyone.
+    ///
+    /// These cards are contained in `hands` as well. In community card games this is empty.
+    pub up_cards: Vec<Vec<Card>>,
 
     /// The actions taken so far in this round.
     pub actions: Vec<Action>,
@@ -47,8 +68,53 @@ pub struct TransparentState {
     /// It is indexed by player position (or also referenced to as the player id).
     pub player_stacks: Vec<ChipCount>,
 
+    /// Whether a player is seated at each seat of the table, indexed by player position.
+    ///
+    /// Empty seats are not dealt in and their stack is zero.
+    pub seated: Vec<bool>,
+
+    /// Whether the player at each seat sits out, indexed by player position.
+    ///
+    /// Players sitting out keep their seat but are not dealt in, see `Table::set_sitting_out`.
+    pub sitting_out: Vec<bool>,
+
+    /// Whether the player at each seat missed the big blind while sitting out and has not made
+    /// up for it yet, indexed by player position. See `missed_blinds`.
+    pub owes_blinds: Vec<bool>,
+
+    /// The rule for players returning after they missed the big blind.
+    pub missed_blinds: MissedBlinds,
+
+    // the players who were seated but not dealt in last round
+    sat_out: Vec<bool>,
+    // the players who are dealt in again after sitting out
+    pub(crate) returning: Vec<bool>,
+
     /// Unique identifier for the current round played.
     pub id: usize,
+
+    /// The betting structure which limits the size of bets and raises.
+    pub betting_structure: BettingStructure,
+
+    /// The game variant which determines the number of hole cards and how hands are ranked.
+    pub variant: GameVariant,
+
+    /// The size of the ante each player pays before the blinds, zero if there are no antes.
+    ///
+    /// If the blind size is zero as well, the minimum bet is equal to the ante.
+    pub ante: ChipCount,
+
+    /// The kind of straddle offered after the blinds, if any.
+    pub straddle: Option<Straddle>,
+
+    /// The amount each player posts instead of the ante if the current round is a bomb pot,
+    /// `None` otherwise. See `RoundOptions`.
+    pub bomb_pot: Option<ChipCount>,
+
+    /// Whether the current round is played on two boards, see `Table::with_double_board`.
+    ///
+    /// Each pot is split evenly between the boards, odd chips go to the first board.
+    pub double_board: bool,
 }
 
 /// Convenience structure wrapping a `TransparentState` for replay purposes.
@@ -63,11 +129,46 @@ pub(crate) struct BetRoundState {
     i: usize,
     last_raiser: Option<usize>,
     done: bool,
+    // the action of a player who timed out, which is recorded after the timeout
+    pending: Option<Action>,
+    // the decision of the next player to act if it was taken in advance, see `Round::decide`
+    decision: Option<PlayerAction>,
+    // whether that decision was taken because the player timed out, see `Round::decide_on_timeout`
+    timed_out: bool,
 }
 
 impl BetRoundState {
     pub(crate) fn done(&self) -> bool {
-        self.done
+        self.done && self.pending.is_none()
+    }
+
+    /// Takes the given decision for the next player to act instead of asking the player.
+    pub(crate) fn decide(&mut self, decision: PlayerAction) {
+        self.decision = Some(decision);
+    }
+
+    /// Takes the given decision for the next player to act, who did not decide in time.
+    pub(crate) fn decide_on_timeout(&mut self, decision: PlayerAction) {
+        self.decision = Some(decision);
+        self.timed_out = true;
+    }
+
+    /// Check whether the decision of the next player to act was taken already, see `decide`.
+    pub(crate) fn decided(&self) -> bool {
+        self.decision.is_some()
+    }
+
+    /// A bet round which is skipped entirely, i. e. the pre-flop betting of a bomb pot.
+    pub(crate) fn skipped() -> Self {
+        Self {
+            index_of_starting_position: 0,
+            i: 0,
+            last_raiser: None,
+            done: true,
+            pending: None,
+            decision: None,
+            timed_out: false,
+        }
     }
 }
 
@@ -101,18 +202,33 @@ impl TransparentState {
             value: crate::deck::card::Value::Ace,
             suit: crate::deck::card::Suit::Club,
         };
-        let hands = vec![[default_card, default_card]; player_stacks.len()];
+        let hands = vec![vec![default_card, default_card]; player_stacks.len()];
 
         Self {
             board: Board::new(),
+            second_board: Board::new(),
+            run_boards: Vec::new(),
             hands,
+            up_cards: vec![Vec::new(); player_stacks.len()],
             actions: Vec::new(),
             pot: Pot::new(player_stacks.len()),
             blind_size,
             dealer_position,
             player_positions: generate_player_positions(dealer_position, player_stacks.len()),
+            seated: vec![true; player_stacks.len()],
+            sitting_out: vec![false; player_stacks.len()],
+            owes_blinds: vec![false; player_stacks.len()],
+            missed_blinds: MissedBlinds::default(),
+            sat_out: vec![false; player_stacks.len()],
+            returning: vec![false; player_stacks.len()],
             player_stacks,
             id: 0,
+            betting_structure: BettingStructure::default(),
+            variant: GameVariant::default(),
+            ante: 0,
+            straddle: None,
+            bomb_pot: None,
+            double_board: false,
         }
     }
 
@@ -129,35 +245,216 @@ impl TransparentState {
     /// Pre-emptively reserves the cards for each player from the given deck
     pub(crate) fn prepare_hands(&mut self, d: &mut impl Deck) {
         for &i in self.player_positions.iter() {
-            let c1 = d.deal().expect("Deck should contain enough cards");
-            let c2 = d.deal().expect("Deck should contain enough cards");
-            self.hands[i] = [c1, c2];
+            self.hands[i] = (0..self.variant.hole_cards())
+                .map(|_| d.deal().expect("Deck should contain enough cards"))
+                .collect();
         }
     }
 
     /// Query the cards which were dealt to the player at the given position.
     ///
     /// Use responsibly.
-    pub fn query_cards(&self, player_position: usize) -> [Card; 2] {
-        self.hands[player_position]
+    pub fn query_cards(&self, player_position: usize) -> &[Card] {
+        &self.hands[player_position]
+    }
+
+    /// Returns the minimum and the maximum number of chips the player at the given position
+    /// may place in order to bet or raise.
+    ///
+    /// Both limits are not restricted by the stack of the player. If the maximum exceeds the
+    /// stack the player may push all-in.
+    ///
+    /// Returns `None` if the player may not raise anymore, i. e. because the raise cap of a
+    /// fixed-limit game is reached. Fixed-limit games without blinds and antes have no bet size,
+    /// hence bets are never possible.
+    pub fn bet_limits(&self, player_position: usize) -> Option<(ChipCount, ChipCount)> {
+        let req_bet = self.pot.required_bet_size(player_position);
+        let min_raise = match self.completion() {
+            Some(completion) => req_bet + completion,
+            None => std::cmp::max(self.pot.last_raise_amount(), self.min_bet()) + req_bet,
+        };
+
+        match self.betting_structure {
+            BettingStructure::NoLimit => Some((min_raise, ChipCount::MAX)),
+            BettingStructure::PotLimit => {
+                let max_raise = req_bet + self.pot.total_size() + req_bet;
+                Some((std::cmp::min(min_raise, max_raise), max_raise))
+            }
+            BettingStructure::FixedLimit { raise_cap } => {
+                // small bets before the turn (fifth street in stud games), big bets afterwards
+                let big_bets = if self.variant.is_stud() {
+                    self.up_cards[player_position].len() >= 3
+                } else {
+                    self.board.turn().is_some()
+                };
+                let bet_size = if big_bets {
+                    self.min_bet() * 2
+                } else {
+                    self.min_bet()
+                };
+                if bet_size == 0 {
+                    return None;
+                }
+                // the big blind counts as the first bet pre-flop
+                let num_bets = (self.pot.bet_size_round() / bet_size) as usize;
+                if num_bets >= raise_cap && self.num_players() > 2 {
+                    None
+                } else {
+                    let raise = self.completion().unwrap_or(bet_size);
+                    Some((req_bet + raise, req_bet + raise))
+                }
+            }
+        }
+    }
+
+    /// The minimum size of a bet, i. e. the big blind or the ante in games without blinds.
+    ///
+    /// If a straddle was posted, it replaces the big blind pre-flop.
+    fn min_bet(&self) -> ChipCount {
+        if self.blind_size == 0 {
+            self.ante
+        } else {
+            match self.posted_straddle() {
+                Some((_, size)) if self.board.flop().is_none() => size,
+                _ => self.blind_size * 2,
+            }
+        }
+    }
+
+    /// Returns the position of the player who posted a straddle this round and its size.
+    fn posted_straddle(&self) -> Option<(usize, ChipCount)> {
+        self.actions.iter().find_map(|action| match action {
+            Action::Straddle(position, size) => Some((*position, *size)),
+            _ => None,
+        })
+    }
+
+    /// The number of chips required to complete the bring-in of a stud game to a full bet.
+    ///
+    /// Returns `None` if there is no bring-in to be completed in the current betting round.
+    fn completion(&self) -> Option<ChipCount> {
+        let bet_size_round = self.pot.bet_size_round();
+        if self.variant.is_stud() && bet_size_round > 0 && bet_size_round < self.min_bet() {
+            Some(self.min_bet() - bet_size_round)
+        } else {
+            None
+        }
+    }
+
+    /// Adds empty seats to the table until there are `num_seats` seats in total.
+    pub(crate) fn add_seats(&mut self, num_seats: usize) {
+        let hand = self.hands[0].clone();
+        self.hands.resize(num_seats, hand);
+        self.up_cards.resize(num_seats, Vec::new());
+        self.player_stacks.resize(num_seats, 0);
+        self.seated.resize(num_seats, false);
+        self.sitting_out.resize(num_seats, false);
+        self.owes_blinds.resize(num_seats, false);
+        self.sat_out.resize(num_seats, false);
+        self.returning.resize(num_seats, false);
+        self.pot = Pot::new(num_seats);
+    }
+
+    /// Marks the given seat as empty, resetting the state of the player who left it.
+    pub(crate) fn empty_seat(&mut self, seat: usize) {
+        self.seated[seat] = false;
+        self.sitting_out[seat] = false;
+        self.owes_blinds[seat] = false;
+        self.sat_out[seat] = false;
+    }
+
+    /// Whether the player at the given seat is dealt into the next round, i. e. the player is
+    /// seated with chips left and does not sit out.
+    pub(crate) fn is_active(&self, seat: usize) -> bool {
+        self.seated[seat] && !self.sitting_out[seat] && self.player_stacks[seat] > 0
     }
 
     /// Resets the internal state, progresses the dealer position and prepares the next round
+    ///
+    /// The dealer button moves to the next seated player who does not sit out or wait for the
+    /// big blind.
     pub(crate) fn reset(&mut self) {
-        self.dealer_position = (self.dealer_position + 1) % self.num_players_total();
+        let num_seats = self.num_players_total();
+        let active: Vec<bool> = (0..num_seats).map(|pos| self.is_active(pos)).collect();
+        let missed_blinds = self.blind_size > 0 && !self.variant.is_stud();
+        // players waiting for the big blind do not get the button
+        let waiting = missed_blinds && self.missed_blinds == MissedBlinds::WaitForBigBlind;
+        let dealer_position = self.dealer_position;
+        self.dealer_position = (1..=num_seats)
+            .map(|i| (dealer_position + i) % num_seats)
+            .find(|&pos| active[pos] && !(waiting && self.owes_blinds[pos]))
+            .unwrap_or(dealer_position);
         self.board.clear();
+        self.second_board.clear();
+        self.run_boards.clear();
+        for up_cards in self.up_cards.iter_mut() {
+            up_cards.clear();
+        }
         self.actions.clear();
         self.pot.reset();
-        self.player_positions =
-            generate_player_positions(self.dealer_position, self.num_players_total());
+        let order = generate_player_positions(self.dealer_position, num_seats);
+        self.player_positions = order.iter().copied().filter(|&pos| active[pos]).collect();
+        if missed_blinds {
+            self.apply_missed_blinds_rule(&order);
+        }
+        for pos in 0..num_seats {
+            let dealt_in = self.player_positions.contains(&pos);
+            self.returning[pos] = self.sat_out[pos] && dealt_in;
+            self.sat_out[pos] = self.seated[pos] && !dealt_in;
+        }
         self.actions.clear();
         self.id += 1;
     }
 
+    /// Keeps track of the big blinds missed by players sitting out and removes the players who
+    /// wait for the big blind from the round, see `MissedBlinds`.
+    ///
+    /// `order` contains all the seats starting with the small blind.
+    fn apply_missed_blinds_rule(&mut self, order: &[usize]) {
+        let seated = &self.seated;
+        if let Some(&big_blind) = order.iter().filter(|&&pos| seated[pos]).nth(1) {
+            if self.sitting_out[big_blind] {
+                self.owes_blinds[big_blind] = true;
+            }
+        }
+
+        if self.missed_blinds == MissedBlinds::WaitForBigBlind {
+            let owes_blinds = &self.owes_blinds;
+            let waiting: Vec<usize> = self
+                .player_positions
+                .iter()
+                .enumerate()
+                .filter(|&(i, &pos)| i != 1 && owes_blinds[pos])
+                .map(|(_, &pos)| pos)
+                .collect();
+            // the round is played anyway if there are not enough players otherwise
+            if self.player_positions.len() - waiting.len() > 1 {
+                self.player_positions.retain(|pos| !waiting.contains(pos));
+            }
+        }
+
+        // posting the big blind makes up for the missed one
+        if let Some(&big_blind) = self.player_positions.get(1) {
+            self.owes_blinds[big_blind] = false;
+        }
+    }
+
+    /// Records whether the player at the given seat sits out or returns after sitting out this
+    /// round.
+    pub(crate) fn seat_action(&mut self, seat: usize) -> Option<Action> {
+        if self.returning[seat] {
+            Some(self.mirrored_action(Action::Return(seat)))
+        } else if self.seated[seat] && !self.player_positions.contains(&seat) {
+            Some(self.mirrored_action(Action::SitOut(seat)))
+        } else {
+            None
+        }
+    }
+
     /// Deals the prepared hand to the player with the given id
     pub(crate) fn deal_hand(&mut self, i: usize) -> Action {
         let pos = self.player_positions[i];
-        self.mirrored_action(Action::DealHand(pos, self.hands[pos]))
+        self.mirrored_action(Action::DealHand(pos, self.hands[pos].clone()))
     }
 
     /// Emits an `Action::StartRound`
@@ -166,30 +463,224 @@ impl TransparentState {
             id: self.id,
             small_blind: self.blind_size,
             big_blind: self.blind_size * 2,
+            dealer_position: self.dealer_position,
+            player_stacks: self.player_stacks.clone(),
+            betting_structure: self.betting_structure,
+            variant: self.variant,
+            ante: self.ante,
+            bomb_pot: self.bomb_pot,
         })
     }
 
-    pub(crate) fn apply_small_blind<P: Player>(&mut self, players: &mut [P]) -> Action {
+    /// The size of the ante paid in the current round, i. e. the agreed amount in a bomb pot.
+    pub(crate) fn ante_size(&self) -> ChipCount {
+        self.bomb_pot.unwrap_or(self.ante)
+    }
+
+    /// Forces the player at the given index of `player_positions` to pay the ante, see `ante_size`.
+    ///
+    /// The antes do not count as bets, hence the betting starts afresh after the last ante.
+    pub(crate) fn apply_ante<P: Player>(
+        &mut self,
+        i: usize,
+        players: &mut impl IndexMut<usize, Output = P>,
+    ) -> Action {
+        let position = self.player_positions[i];
+        let ante = std::cmp::min(self.ante_size(), self.player_stacks[position]);
+        self.pot.place_chips(position, ante);
+
+        // there is only one possible action, see `blind`
+        let player_action = PlayerAction::Ante(ante);
+        players[position].act(self, &[player_action]);
+        let action =
+            Action::from_player_action(player_action, position, self.player_stacks[position]);
+        self.player_stacks[position] -= ante;
+
+        if i + 1 == self.num_players() {
+            self.pot.end_bet_round();
+        }
+        self.mirrored_action(action)
+    }
+
+    pub(crate) fn apply_small_blind<P: Player>(
+        &mut self,
+        players: &mut impl IndexMut<usize, Output = P>,
+    ) -> Action {
         let action = self.blind(players, self.player_positions[0], self.blind_size);
         self.mirrored_action(action)
     }
 
-    pub(crate) fn apply_big_blind<P: Player>(&mut self, players: &mut [P]) -> Action {
+    pub(crate) fn apply_big_blind<P: Player>(
+        &mut self,
+        players: &mut impl IndexMut<usize, Output = P>,
+    ) -> Action {
         let action = self.blind(players, self.player_positions[1], self.blind_size * 2);
         self.mirrored_action(action)
     }
 
+    /// Forces the player at the given index of `player_positions` to post the missed blinds if
+    /// the player returned after missing the big blind, see `MissedBlinds::PostBoth`.
+    ///
+    /// The small blind is dead, the big blind is live. Returns `None` if the player does not owe
+    /// any blinds or has no chips left.
+    pub(crate) fn apply_missed_blinds<P: Player>(
+        &mut self,
+        i: usize,
+        players: &mut impl IndexMut<usize, Output = P>,
+    ) -> Option<Action> {
+        let position = self.player_positions[i];
+        if !self.owes_blinds[position] || self.player_stacks[position] == 0 {
+            return None;
+        }
+        self.owes_blinds[position] = false;
+
+        let stack = self.player_stacks[position];
+        let dead = std::cmp::min(self.blind_size, stack);
+        let live = std::cmp::min(self.blind_size * 2, stack - dead);
+        self.pot.place_dead_chips(dead);
+        self.pot.place_chips(position, live);
+
+        // there is only one possible action, see `blind`
+        let player_action = if dead + live == stack {
+            PlayerAction::AllIn(stack)
+        } else {
+            PlayerAction::Blind(dead + live)
+        };
+        players[position].act(self, &[player_action]);
+        self.player_stacks[position] -= dead + live;
+        Some(self.mirrored_action(Action::MissedBlinds(position, dead + live)))
+    }
+
+    /// Offers a straddle of twice the big blind to the player at the position given by
+    /// `straddle`, see `Straddle`.
+    ///
+    /// Returns `None` if no straddle is offered or the player declines to straddle. The straddle
+    /// is not offered to players who would have to go all-in.
+    pub(crate) fn apply_straddle<P: Player>(
+        &mut self,
+        players: &mut impl IndexMut<usize, Output = P>,
+    ) -> Option<Action> {
+        if self.blind_size == 0 || self.num_players() < 3 {
+            return None;
+        }
+        let i = match self.straddle? {
+            Straddle::UnderTheGun => 2,
+            Straddle::Button => self.num_players() - 1,
+        };
+        let position = self.player_positions[i];
+        let size = self.blind_size * 4;
+        if self.player_stacks[position] <= size || !players[position].straddle(self, size) {
+            return None;
+        }
+
+        self.pot.place_chips(position, size);
+        self.player_stacks[position] -= size;
+        Some(self.mirrored_action(Action::Straddle(position, size)))
+    }
+
+    /// Deals the given card face up to the player at the given index of `player_positions`.
+    pub(crate) fn deal_up(&mut self, i: usize, card: Card) -> Action {
+        let pos = self.player_positions[i];
+        self.hands[pos].push(card);
+        self.up_cards[pos].push(card);
+        self.mirrored_action(Action::DealUp(pos, card))
+    }
+
+    /// Deals the given card face down to the player at the given index of `player_positions`.
+    pub(crate) fn deal_down(&mut self, i: usize, card: Card) -> Action {
+        let pos = self.player_positions[i];
+        self.hands[pos].push(card);
+        self.mirrored_action(Action::DealDown(pos, card))
+    }
+
+    /// Returns the index of `player_positions` of the player showing the lowest up card.
+    ///
+    /// Ties are broken by suit in bridge order, i. e. clubs are the lowest suit, followed by
+    /// diamonds, hearts and spades.
+    pub(crate) fn bring_in_index(&self) -> usize {
+        let suit_order = |suit: Suit| match suit {
+            Suit::Club => 0,
+            Suit::Diamond => 1,
+            Suit::Heart => 2,
+            Suit::Spade => 3,
+        };
+        (0..self.num_players())
+            .min_by_key(|&i| {
+                let card = self.up_cards[self.player_positions[i]][0];
+                (card.value, suit_order(card.suit))
+            })
+            .expect("There should be active players")
+    }
+
+    /// Returns the index of `player_positions` of the player showing the best hand.
+    ///
+    /// Ties are broken in favor of the player closest to the left of the dealer.
+    pub(crate) fn best_showing_index(&self) -> usize {
+        let mut best = 0;
+        let mut best_rank = self.up_cards[self.player_positions[0]].as_slice().rank();
+        for i in 1..self.num_players() {
+            let rank = self.up_cards[self.player_positions[i]].as_slice().rank();
+            if rank > best_rank {
+                best = i;
+                best_rank = rank;
+            }
+        }
+        best
+    }
+
+    /// Forces the player at the given index of `player_positions` to bring in.
+    ///
+    /// The size of the bring-in is equal to `blind_size`.
+    pub(crate) fn apply_bring_in<P: Player>(
+        &mut self,
+        i: usize,
+        players: &mut impl IndexMut<usize, Output = P>,
+    ) -> Action {
+        let position = self.player_positions[i];
+        let action = self.forced_bet(players, position, self.blind_size, PlayerAction::BringIn);
+        self.mirrored_action(action)
+    }
+
+    /// Create a state object which can be used in `step_bet_round` until the bet round finished
+    ///
+    /// This method shall be used for betting in stud games, which starts with the player at
+    /// index `i` of `player_positions`. If the player at `bring_in` was forced to bring in, the
+    /// betting round ends once the action is back at this player, unless anyone completed.
+    pub(crate) fn init_stud_action(&self, i: usize, bring_in: Option<usize>) -> BetRoundState {
+        BetRoundState {
+            i,
+            index_of_starting_position: i,
+            last_raiser: bring_in,
+            done: false,
+            pending: None,
+            decision: None,
+            timed_out: false,
+        }
+    }
+
     /// Create a state object which can be used in `step_bet_round` until the bet round finished
     ///
     /// This method shall be used for betting **before** the flop has been dealt.
     pub(crate) fn init_pre_flop_action(&self) -> BetRoundState {
-        // pre-flop action starts at big blind + 1
-        let i = 2 % self.num_players();
+        // pre-flop action starts left of the last live blind, i. e. the big blind or the
+        // straddle, or left of the dealer if there are no blinds
+        let i = if self.blind_size == 0 {
+            0
+        } else {
+            let last_blind = self
+                .posted_straddle()
+                .and_then(|(pos, _)| self.player_positions.iter().position(|&p| p == pos))
+                .unwrap_or(1);
+            (last_blind + 1) % self.num_players()
+        };
         BetRoundState {
             i,
             index_of_starting_position: i,
             last_raiser: None,
             done: false,
+            pending: None,
+            decision: None,
+            timed_out: false,
         }
     }
 
@@ -202,6 +693,9 @@ impl TransparentState {
             index_of_starting_position: 0,
             last_raiser: None,
             done: false,
+            pending: None,
+            decision: None,
+            timed_out: false,
         }
     }
 
@@ -215,20 +709,36 @@ impl TransparentState {
     pub(crate) fn step_bet_round<P: Player>(
         &mut self,
         state: &mut BetRoundState,
-        players: &mut [P],
+        players: &mut impl IndexMut<usize, Output = P>,
     ) -> Option<Action> {
+        if let Some(action) = state.pending.take() {
+            return Some(self.mirrored_action(action));
+        }
         if state.done() {
             return None;
         }
 
         let pos = self.player_positions[state.i];
 
-        let (action, is_raise) = self.player_action(pos, &mut players[pos]);
+        let timed_out = std::mem::take(&mut state.timed_out);
+        let (action, is_raise) = self.player_action(pos, &mut players[pos], &mut state.decision);
+        // the timeout is recorded first, the action follows in the next step
+        let action = match action {
+            Some(action) if timed_out => {
+                state.pending = Some(action);
+                Some(Action::Timeout(pos))
+            }
+            action => action,
+        };
         if is_raise {
             state.last_raiser = Some(pos);
         }
-        if let Some(Action::Fold(_)) = action {
+        if let Some(Action::Fold(_)) = state.pending.as_ref().or(action.as_ref()) {
             self.player_positions.remove(state.i);
+            if state.i < state.index_of_starting_position {
+                // the starting position moved one index ahead
+                state.index_of_starting_position -= 1;
+            }
             if state.last_raiser.is_none() && state.i == state.index_of_starting_position {
                 // this is the special case when pre-flop players only either fold or call to the big-blind
                 state.i %= self.num_players();
@@ -244,19 +754,40 @@ impl TransparentState {
         }
 
         state.i %= self.num_players();
-        if Some(self.player_positions[state.i]) == state.last_raiser
-            || (state.last_raiser.is_none() && state.i == state.index_of_starting_position)
-            || self.num_players() == 1
-        {
+        if self.bet_round_done(state) {
             self.pot.end_bet_round();
             state.done = true;
         }
 
-        if let Some(action) = action {
-            Some(self.mirrored_action(action))
-        } else {
-            None
+        action.map(|action| self.mirrored_action(action))
+    }
+
+    /// Check whether the betting ends before the player at the current index of the given bet
+    /// round acts.
+    fn bet_round_done(&self, state: &BetRoundState) -> bool {
+        Some(self.player_positions[state.i]) == state.last_raiser
+            || (state.last_raiser.is_none() && state.i == state.index_of_starting_position)
+            || self.num_players() == 1
+    }
+
+    /// Returns the position of the player who decides next in the given bet round, or `None` if
+    /// the bet round ends before any other decision.
+    ///
+    /// Players without chips left are skipped the same way as in `step_bet_round`.
+    pub(crate) fn next_to_act(&self, state: &BetRoundState) -> Option<usize> {
+        if state.pending.is_some() {
+            return None;
+        }
+        let mut state = state.clone();
+        while !state.done {
+            let pos = self.player_positions[state.i];
+            if self.player_stacks[pos] > 0 {
+                return Some(pos);
+            }
+            state.i = (state.i + 1) % self.num_players();
+            state.done = self.bet_round_done(&state);
         }
+        None
     }
 
     pub(crate) fn deal_flop(&mut self, cards: [Card; 3]) -> Action {
@@ -274,6 +805,62 @@ impl TransparentState {
         self.mirrored_action(Action::DealRiver(card))
     }
 
+    /// Check whether at most one of the remaining players has chips left, i. e. no more betting
+    /// is possible.
+    pub(crate) fn all_in(&self) -> bool {
+        self.player_positions
+            .iter()
+            .filter(|&&pos| self.player_stacks[pos] > 0)
+            .count()
+            <= 1
+    }
+
+    /// Deals the next street of the board of the run with the given index.
+    ///
+    /// Takes three cards from `next_cards` for the flop or one card otherwise.
+    pub(crate) fn deal_run(&mut self, run: usize, next_cards: &mut Vec<Card>) -> Action {
+        let action = deal_street(&mut self.board, next_cards);
+        self.mirrored_action(Action::Run(run, Box::new(action)))
+    }
+
+    /// Deals the street of the second board which was dealt to the first board last.
+    ///
+    /// Takes three cards from `next_cards` for the flop or one card otherwise.
+    pub(crate) fn deal_second_board(&mut self, next_cards: &mut Vec<Card>) -> Action {
+        let action = deal_street(&mut self.second_board, next_cards);
+        self.mirrored_action(Action::SecondBoard(Box::new(action)))
+    }
+
+    /// Splits the pot into the main pot and the side pots contested by the remaining players and
+    /// removes all the chips from the pot, see `Pot::take_side_pots`.
+    pub(crate) fn take_side_pots(&mut self) -> Vec<(ChipCount, Vec<usize>)> {
+        self.pot.take_side_pots(&self.player_positions)
+    }
+
+    /// Awards the share of each of the given pots which is won on the board of the run with the
+    /// given index.
+    ///
+    /// Each pot is split evenly across the runs, odd chips go to the first run.
+    pub(crate) fn award_run(
+        &mut self,
+        run: usize,
+        runs: usize,
+        side_pots: &[(ChipCount, Vec<usize>)],
+    ) -> Action {
+        let shares = side_pots
+            .iter()
+            .map(|(size, eligible)| {
+                let mut share = size / runs as ChipCount;
+                if run == 0 {
+                    share += size % runs as ChipCount;
+                }
+                (share, eligible.clone())
+            })
+            .collect();
+        let wins = self.award_side_pots(shares, &[self.board]);
+        self.mirrored_action(Action::Run(run, Box::new(Action::Win(wins))))
+    }
+
     pub(crate) fn end_round(&mut self) -> Action {
         if self.num_players() == 1 {
             // the player left gets the pot
@@ -281,14 +868,24 @@ impl TransparentState {
             let win = self.pot.distribute(&self.player_positions)[pos];
             self.player_stacks[pos] += win;
 
-            Action::Win(vec![(pos, win)])
+            self.mirrored_action(Action::Win(vec![(pos, win)]))
+        } else if self.double_board {
+            let side_pots = self.take_side_pots();
+            let wins = self.award_side_pots(side_pots, &[self.board, self.second_board]);
+            self.mirrored_action(Action::Win(wins))
+        } else if self.variant.is_split() {
+            let wins = self.distribute_hi_lo();
+            self.mirrored_action(Action::Win(wins))
         } else {
             // prepare showdown
             let mut ranked_hands = Vec::new();
             for &i in self.player_positions.iter() {
-                ranked_hands.push((self.board.rank_hand(self.hands[i]), i))
+                let rank = self
+                    .variant
+                    .rank_hand(&self.hands[i], self.board.all_cards());
+                ranked_hands.push((rank, i))
             }
-            ranked_hands.sort_by_key(|x| x.0.clone());
+            ranked_hands.sort_by(|a, b| self.variant.cmp_hands(&a.0, &b.0));
             let mut wins = Vec::new();
 
             while let Some((rank, pos)) = ranked_hands.pop() {
@@ -309,31 +906,106 @@ impl TransparentState {
                 }
             }
 
-            Action::Win(wins)
+            self.mirrored_action(Action::Win(wins))
         }
     }
 
+    /// Distributes each (side) pot between the best high hand and the best qualifying low hand.
+    ///
+    /// If there is no qualifying low hand the best high hand wins the whole pot. Odd chips of
+    /// a pot which is split between the high and the low hand go to the high hand, odd chips
+    /// of a split among tied hands go to the first of those players after the dealer.
+    fn distribute_hi_lo(&mut self) -> Vec<(usize, ChipCount)> {
+        let side_pots = self.take_side_pots();
+        self.award_side_pots(side_pots, &[self.board])
+    }
+
+    /// Awards each of the given pots to the best hands of the eligible players on the given
+    /// boards and returns the wins.
+    ///
+    /// Each pot is split evenly between the boards, odd chips go to the first board. In split
+    /// games the pots are split between the high and the low hand, see `distribute_hi_lo`.
+    fn award_side_pots(
+        &mut self,
+        side_pots: Vec<(ChipCount, Vec<usize>)>,
+        boards: &[Board],
+    ) -> Vec<(usize, ChipCount)> {
+        let variant = self.variant;
+        let num_boards = boards.len() as ChipCount;
+        let mut wins = Vec::new();
+        for (i, board) in boards.iter().enumerate() {
+            let board = board.all_cards();
+            let mut high_ranks = vec![None; self.num_players_total()];
+            let mut low_ranks = vec![None; self.num_players_total()];
+            for &pos in self.player_positions.iter() {
+                high_ranks[pos] = Some(variant.rank_hand(&self.hands[pos], board));
+                low_ranks[pos] = variant.rank_low(&self.hands[pos], board);
+            }
+
+            for (pot, eligible) in side_pots.iter() {
+                let mut size = pot / num_boards;
+                if i == 0 {
+                    size += pot % num_boards;
+                }
+                let high_winners =
+                    best_hands(eligible, &high_ranks, |a, b| variant.cmp_hands(a, b));
+                let low_winners = best_hands(eligible, &low_ranks, Ord::cmp);
+                if low_winners.is_empty() {
+                    award(&mut wins, &high_winners, size);
+                } else {
+                    // the odd chip goes to the high hand
+                    award(&mut wins, &high_winners, size - size / 2);
+                    award(&mut wins, &low_winners, size / 2);
+                }
+            }
+        }
+
+        for &(pos, amount) in wins.iter() {
+            self.player_stacks[pos] += amount;
+        }
+        wins
+    }
+
     /// Forces the player at `position` to set a blind of the specified size.
     ///
     /// Takes care of adjusting stack size and pot size. Forces a player All-In if
     /// it has not enough chips available.
     ///
     /// Returns the corresponding action taken
-    fn blind<P: Player>(&mut self, players: &mut [P], position: usize, size: ChipCount) -> Action {
+    fn blind<P: Player>(
+        &mut self,
+        players: &mut impl IndexMut<usize, Output = P>,
+        position: usize,
+        size: ChipCount,
+    ) -> Action {
+        self.forced_bet(players, position, size, PlayerAction::Blind)
+    }
+
+    /// Forces the player at `position` to place a bet of the specified size, i. e. a blind
+    /// or a bring-in, which is described by `forced_action`.
+    ///
+    /// See `blind` for details.
+    fn forced_bet<P: Player>(
+        &mut self,
+        players: &mut impl IndexMut<usize, Output = P>,
+        position: usize,
+        size: ChipCount,
+        forced_action: fn(ChipCount) -> PlayerAction,
+    ) -> Action {
         let actual_bet_size;
         let player_action = if self.player_stacks[position] <= size {
             actual_bet_size = self.player_stacks[position];
             PlayerAction::AllIn(self.player_stacks[position])
         } else {
             actual_bet_size = size;
-            PlayerAction::Blind(size)
+            forced_action(size)
         };
 
         self.pot.place_chips(position, actual_bet_size);
 
         // we ignore the return value as there is only one possible action anyway
         // we could consider checking back in order to ensure that players are implemented correctly
-        players[position].act(&self, &[player_action]);
+        players[position].act(self, &[player_action]);
         let action_taken =
             Action::from_player_action(player_action, position, self.player_stacks[position]);
 
@@ -345,10 +1017,12 @@ impl TransparentState {
     ///
     /// This function returns a pair of the action taken (if any) and a boolean indicating if the action taken can be considered a raise (i.e. Bet, Raise, AllIn which raised).
     /// The only case in which no action is taken, is if the player at the given position does not have any chips left.
+    /// If a `decision` was taken in advance, it is taken instead of asking the player.
     fn player_action(
         &mut self,
         position: usize,
         player: &mut impl Player,
+        decision: &mut Option<PlayerAction>,
     ) -> (Option<Action>, bool) {
         let stack = self.player_stacks[position];
         if stack == 0 {
@@ -356,29 +1030,28 @@ impl TransparentState {
         }
 
         let req_bet = self.pot.required_bet_size(position);
-        let min_raise = std::cmp::max(self.pot.last_raise_amount(), self.blind_size * 2) + req_bet;
-
-        let mut possible_actions = vec![PlayerAction::AllIn(stack)];
-
-        if req_bet == 0 {
-            possible_actions.push(PlayerAction::Check);
-        } else {
-            possible_actions.push(PlayerAction::Fold);
-            if req_bet < stack {
-                possible_actions.push(PlayerAction::Call(req_bet));
-            }
-        }
-
-        if min_raise < stack {
-            if req_bet == 0 {
-                possible_actions.push(PlayerAction::Bet(min_raise));
-            } else {
-                possible_actions.push(PlayerAction::Raise(min_raise));
+        let limits = self.bet_limits(position);
+        // if raising is not possible the player may only call
+        let max_raise = limits.map(|(_, max)| max).unwrap_or(req_bet);
+
+        let possible_actions = self.possible_actions(position);
+        let action = match decision.take() {
+            Some(action) => action,
+            None => player.act(self, &possible_actions),
+        };
+        let action = match Action::from_player_action(action, position, stack) {
+            // bets exceeding the limit are reduced to the maximum allowed
+            Action::Bet(_, c) | Action::Raise(_, c) | Action::AllIn(_, c) if c > max_raise => {
+                if limits.is_none() {
+                    Action::Call(position, req_bet)
+                } else if req_bet == 0 {
+                    Action::Bet(position, max_raise)
+                } else {
+                    Action::Raise(position, max_raise)
+                }
             }
-        }
-
-        let action = player.act(&self, &possible_actions);
-        let action = Action::from_player_action(action, position, stack);
+            action => action,
+        };
 
         let actual_bet_size = match action {
             Action::Bet(_, c) | Action::Raise(_, c) | Action::Call(_, c) | Action::AllIn(_, c) => {
@@ -397,12 +1070,118 @@ impl TransparentState {
         (Some(action), is_raise)
     }
 
+    /// The actions the player at the given position may choose from, given that the player has
+    /// chips left.
+    pub(crate) fn possible_actions(&self, position: usize) -> Vec<PlayerAction> {
+        let stack = self.player_stacks[position];
+        let req_bet = self.pot.required_bet_size(position);
+        let limits = self.bet_limits(position);
+        // if raising is not possible the player may only call
+        let max_raise = limits.map(|(_, max)| max).unwrap_or(req_bet);
+
+        let mut possible_actions = Vec::new();
+        if stack <= max_raise {
+            possible_actions.push(PlayerAction::AllIn(stack));
+        }
+
+        if req_bet == 0 {
+            possible_actions.push(PlayerAction::Check);
+        } else {
+            possible_actions.push(PlayerAction::Fold);
+            if req_bet < stack {
+                possible_actions.push(PlayerAction::Call(req_bet));
+            }
+        }
+
+        if let Some((min_raise, _)) = limits.filter(|&(min_raise, _)| min_raise < stack) {
+            if req_bet == 0 {
+                possible_actions.push(PlayerAction::Bet(min_raise));
+            } else {
+                possible_actions.push(PlayerAction::Raise(min_raise));
+            }
+        }
+        possible_actions
+    }
+
     fn mirrored_action(&mut self, a: Action) -> Action {
         self.actions.push(a.clone());
         a
     }
 }
 
+/// Returns the positions of the players holding the best of the given ranks according to `cmp`.
+///
+/// Players without a rank are not considered.
+fn best_hands<R>(
+    player_positions: &[usize],
+    ranks: &[Option<R>],
+    cmp: impl Fn(&R, &R) -> Ordering,
+) -> Vec<usize> {
+    let best = match player_positions
+        .iter()
+        .filter_map(|&pos| ranks[pos].as_ref())
+        .max_by(|a, b| cmp(a, b))
+    {
+        Some(best) => best,
+        None => return Vec::new(),
+    };
+    player_positions
+        .iter()
+        .copied()
+        .filter(|&pos| {
+            ranks[pos]
+                .as_ref()
+                .is_some_and(|rank| cmp(rank, best) == Ordering::Equal)
+        })
+        .collect()
+}
+
+/// Splits the given number of chips evenly between the given players and adds them to `wins`.
+///
+/// The first player receives the odd chips.
+fn award(wins: &mut Vec<(usize, ChipCount)>, winners: &[usize], size: ChipCount) {
+    for (i, &pos) in winners.iter().enumerate() {
+        let mut amount = size / winners.len() as ChipCount;
+        if i == 0 {
+            amount += size % winners.len() as ChipCount;
+        }
+        if amount == 0 {
+            continue;
+        }
+        match wins.iter_mut().find(|(p, _)| *p == pos) {
+            Some((_, total)) => *total += amount,
+            None => wins.push((pos, amount)),
+        }
+    }
+}
+
+/// Deals the next street of the given board, taking three cards from `next_cards` for the flop
+/// or one card otherwise.
+fn deal_street(board: &mut Board, next_cards: &mut Vec<Card>) -> Action {
+    let mut next_card = || {
+        next_cards
+            .pop()
+            .expect("There should be cards for each board")
+    };
+    match board.all_cards().len() {
+        0 => {
+            let cards = [next_card(), next_card(), next_card()];
+            board.deal_flop(cards);
+            Action::DealFlop(cards)
+        }
+        3 => {
+            let card = next_card();
+            board.deal_turn(card);
+            Action::DealTurn(card)
+        }
+        _ => {
+            let card = next_card();
+            board.deal_river(card);
+            Action::DealRiver(card)
+        }
+    }
+}
+
 fn generate_player_positions(dealer_position: usize, num_players: usize) -> Vec<usize> {
     (0..num_players)
         .map(|x| (x + 1 + dealer_position) % num_players)
@@ -412,7 +1191,9 @@ fn generate_player_positions(dealer_position: usize, num_players: usize) -> Vec<
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::deck::CardCollection;
     use crate::mock::MockPlayer;
+    use std::convert::TryFrom;
 
     fn set_equal<T: PartialEq + Clone>(c1: &[T], c2: &[T]) -> bool {
         let mut c2 = c2.to_vec();
@@ -503,7 +1284,7 @@ mod tests {
         ];
         state.apply_small_blind(&mut players);
         state.apply_big_blind(&mut players);
-        let (action, is_raise) = state.player_action(0, &mut players[0]);
+        let (action, is_raise) = state.player_action(0, &mut players[0], &mut None);
         assert!(!is_raise);
 
         assert!(set_equal(
@@ -530,9 +1311,9 @@ mod tests {
         ];
         state.apply_small_blind(&mut players);
         state.apply_big_blind(&mut players);
-        state.player_action(0, &mut players[0]);
-        state.player_action(1, &mut players[1]);
-        let (action, is_raise) = state.player_action(2, &mut players[2]);
+        state.player_action(0, &mut players[0], &mut None);
+        state.player_action(1, &mut players[1], &mut None);
+        let (action, is_raise) = state.player_action(2, &mut players[2], &mut None);
         assert!(is_raise);
 
         assert!(set_equal(
@@ -558,8 +1339,8 @@ mod tests {
         ];
         state.apply_small_blind(&mut players);
         state.apply_big_blind(&mut players);
-        state.player_action(0, &mut players[0]);
-        let (action, is_raise) = state.player_action(1, &mut players[1]);
+        state.player_action(0, &mut players[0], &mut None);
+        let (action, is_raise) = state.player_action(1, &mut players[1], &mut None);
         assert!(is_raise);
 
         assert!(set_equal(
@@ -586,8 +1367,8 @@ mod tests {
         ];
         state.apply_small_blind(&mut players);
         state.apply_big_blind(&mut players);
-        let (first_action, first_is_raise) = state.player_action(0, &mut players[0]);
-        let (secnd_action, secnd_is_raise) = state.player_action(1, &mut players[1]);
+        let (first_action, first_is_raise) = state.player_action(0, &mut players[0], &mut None);
+        let (secnd_action, secnd_is_raise) = state.player_action(1, &mut players[1], &mut None);
 
         assert!(!first_is_raise);
         assert!(secnd_is_raise);
@@ -622,9 +1403,9 @@ mod tests {
         ];
         state.apply_small_blind(&mut players);
         state.apply_big_blind(&mut players);
-        state.player_action(0, &mut players[0]);
-        state.player_action(1, &mut players[1]);
-        let (action, is_raise) = state.player_action(2, &mut players[2]);
+        state.player_action(0, &mut players[0], &mut None);
+        state.player_action(1, &mut players[1], &mut None);
+        let (action, is_raise) = state.player_action(2, &mut players[2], &mut None);
         assert!(!is_raise);
 
         assert_eq!(state.player_stacks, vec![6, 6, 6]);
@@ -642,7 +1423,7 @@ mod tests {
         ];
         state.apply_small_blind(&mut players);
         state.apply_big_blind(&mut players);
-        let (action, is_raise) = state.player_action(0, &mut players[0]);
+        let (action, is_raise) = state.player_action(0, &mut players[0], &mut None);
         assert!(!is_raise);
 
         assert_eq!(state.player_stacks, vec![10, 8, 6]);
@@ -660,8 +1441,8 @@ mod tests {
         ];
         state.apply_small_blind(&mut players);
         state.apply_big_blind(&mut players);
-        let (first_action, first_is_raise) = state.player_action(0, &mut players[0]);
-        let (secnd_action, secnd_is_raise) = state.player_action(0, &mut players[0]);
+        let (first_action, first_is_raise) = state.player_action(0, &mut players[0], &mut None);
+        let (secnd_action, secnd_is_raise) = state.player_action(0, &mut players[0], &mut None);
         assert!(first_is_raise);
         assert!(!secnd_is_raise);
 
@@ -671,37 +1452,179 @@ mod tests {
         assert_eq!(state.pot.total_size(), 16);
     }
 
+    #[test]
+    fn test_pot_limit_raise() {
+        let mut state = TransparentState::new(2, 2, vec![100, 100, 100]);
+        state.betting_structure = BettingStructure::PotLimit;
+        let mut players = vec![
+            MockPlayer::new(vec![PlayerAction::Blind(2)]), // Small
+            MockPlayer::new(vec![PlayerAction::Blind(4)]), // Big
+            MockPlayer::new(vec![PlayerAction::Raise(50)]),
+        ];
+        state.apply_small_blind(&mut players);
+        state.apply_big_blind(&mut players);
+        assert_eq!(state.bet_limits(2), Some((8, 14)));
+
+        let (action, is_raise) = state.player_action(2, &mut players[2], &mut None);
+        assert!(is_raise);
+
+        // all-in exceeds the size of the pot
+        assert!(set_equal(
+            &players[2].last_possible_actions,
+            &[
+                PlayerAction::Fold,
+                PlayerAction::Call(4),
+                PlayerAction::Raise(8),
+            ]
+        ));
+        // the raise is reduced to the size of the pot
+        assert_eq!(action, Some(Action::Raise(2, 14)));
+        assert_eq!(state.player_stacks, vec![98, 96, 86]);
+        assert_eq!(state.pot.total_size(), 20);
+    }
+
+    #[test]
+    fn test_pot_limit_allin_within_limit() {
+        let mut state = TransparentState::new(2, 2, vec![100, 100, 12]);
+        state.betting_structure = BettingStructure::PotLimit;
+        let mut players = vec![
+            MockPlayer::new(vec![PlayerAction::Blind(2)]), // Small
+            MockPlayer::new(vec![PlayerAction::Blind(4)]), // Big
+            MockPlayer::new(vec![PlayerAction::AllIn(12)]),
+        ];
+        state.apply_small_blind(&mut players);
+        state.apply_big_blind(&mut players);
+        let (action, _) = state.player_action(2, &mut players[2], &mut None);
+
+        assert!(set_equal(
+            &players[2].last_possible_actions,
+            &[
+                PlayerAction::Fold,
+                PlayerAction::Call(4),
+                PlayerAction::Raise(8),
+                PlayerAction::AllIn(12),
+            ]
+        ));
+        assert_eq!(action, Some(Action::AllIn(2, 12)));
+    }
+
+    #[test]
+    fn test_fixed_limit_raise_cap() {
+        let mut state = TransparentState::new(2, 2, vec![100, 100, 100]);
+        state.betting_structure = BettingStructure::FixedLimit { raise_cap: 4 };
+        let mut players = vec![
+            MockPlayer::new(vec![PlayerAction::Blind(2), PlayerAction::Raise(10)]), // Small
+            MockPlayer::new(vec![PlayerAction::Blind(4), PlayerAction::Raise(12)]), // Big
+            MockPlayer::new(vec![PlayerAction::Raise(20), PlayerAction::Call(8)]),
+        ];
+        state.apply_small_blind(&mut players);
+        state.apply_big_blind(&mut players);
+
+        let (action, _) = state.player_action(2, &mut players[2], &mut None);
+        assert!(set_equal(
+            &players[2].last_possible_actions,
+            &[
+                PlayerAction::Fold,
+                PlayerAction::Call(4),
+                PlayerAction::Raise(8),
+            ]
+        ));
+        // the raise is reduced to the fixed size
+        assert_eq!(action, Some(Action::Raise(2, 8)));
+
+        let (action, _) = state.player_action(0, &mut players[0], &mut None);
+        assert_eq!(action, Some(Action::Raise(0, 10)));
+        let (action, _) = state.player_action(1, &mut players[1], &mut None);
+        assert_eq!(action, Some(Action::Raise(1, 12)));
+
+        // four bets were made, the raise cap is reached
+        assert_eq!(state.bet_limits(2), None);
+        let (action, _) = state.player_action(2, &mut players[2], &mut None);
+        assert!(set_equal(
+            &players[2].last_possible_actions,
+            &[PlayerAction::Fold, PlayerAction::Call(8)]
+        ));
+        assert_eq!(action, Some(Action::Call(2, 8)));
+        assert_eq!(state.player_stacks, vec![88, 84, 84]);
+    }
+
+    #[test]
+    fn test_fixed_limit_raise_cap_is_lifted_heads_up() {
+        let mut state = TransparentState::new(2, 2, vec![100, 100, 100]);
+        state.betting_structure = BettingStructure::FixedLimit { raise_cap: 1 };
+        let mut players = vec![
+            MockPlayer::new(vec![PlayerAction::Blind(2)]), // Small
+            MockPlayer::new(vec![PlayerAction::Blind(4)]), // Big
+            MockPlayer::new(vec![]),
+        ];
+        state.apply_small_blind(&mut players);
+        state.apply_big_blind(&mut players);
+        assert_eq!(state.bet_limits(0), None);
+
+        state.player_positions.retain(|&pos| pos != 2);
+        assert_eq!(state.bet_limits(0), Some((6, 6)));
+    }
+
+    #[test]
+    fn test_fixed_limit_big_bet_after_turn() {
+        let mut state = TransparentState::new(2, 2, vec![100, 100, 100]);
+        state.betting_structure = BettingStructure::FixedLimit { raise_cap: 4 };
+        let card = Card {
+            value: crate::deck::card::Value::Ace,
+            suit: crate::deck::card::Suit::Club,
+        };
+        assert_eq!(state.bet_limits(0), Some((4, 4)));
+
+        state.deal_flop([card, card, card]);
+        assert_eq!(state.bet_limits(0), Some((4, 4)));
+
+        state.deal_turn(card);
+        assert_eq!(state.bet_limits(0), Some((8, 8)));
+    }
+
+    #[test]
+    fn test_fixed_limit_without_bet_size() {
+        let mut state = TransparentState::new(0, 2, vec![100, 100, 100]);
+        state.betting_structure = BettingStructure::FixedLimit { raise_cap: 4 };
+        assert_eq!(state.bet_limits(0), None);
+
+        let mut player = MockPlayer::new(vec![PlayerAction::Check]);
+        let (action, _) = state.player_action(0, &mut player, &mut None);
+        assert_eq!(player.last_possible_actions, [PlayerAction::Check]);
+        assert_eq!(action, Some(Action::Check(0)));
+    }
+
     #[test]
     fn test_min_bet_size() {
         let mut state = TransparentState::new(2, 0, vec![1000, 1000, 1000]);
-        let mut players = vec![
+        let mut players = [
             MockPlayer::new(vec![PlayerAction::Call(9), PlayerAction::Raise(6 + 6 + 1)]),
             MockPlayer::new(vec![PlayerAction::Bet(4), PlayerAction::Raise(5 + 5 + 1)]), // Small
             MockPlayer::new(vec![PlayerAction::Raise(4 + 4 + 1), PlayerAction::Call(6)]), // Big
         ];
-        state.player_action(1, &mut players[1]); // Bet(4)
+        state.player_action(1, &mut players[1], &mut None); // Bet(4)
         assert!(set_contains(
             &players[1].last_possible_actions,
             &[PlayerAction::Bet(4)]
         ));
 
-        state.player_action(2, &mut players[2]); // Raise(4+4+1)
+        state.player_action(2, &mut players[2], &mut None); // Raise(4+4+1)
         assert!(set_contains(
             &players[2].last_possible_actions,
             &[PlayerAction::Raise(8)]
         ));
 
-        state.player_action(0, &mut players[0]); // Call(9)
+        state.player_action(0, &mut players[0], &mut None); // Call(9)
 
-        state.player_action(1, &mut players[1]); // Raise(5+5+1)
+        state.player_action(1, &mut players[1], &mut None); // Raise(5+5+1)
         assert!(set_contains(
             &players[1].last_possible_actions,
             &[PlayerAction::Raise(10)]
         ));
 
-        state.player_action(2, &mut players[2]); // Call(6)
+        state.player_action(2, &mut players[2], &mut None); // Call(6)
 
-        state.player_action(0, &mut players[0]); // Raise(6+6+1)
+        state.player_action(0, &mut players[0], &mut None); // Raise(6+6+1)
         assert!(set_contains(
             &players[0].last_possible_actions,
             &[PlayerAction::Raise(12)]
@@ -918,4 +1841,82 @@ mod tests {
         assert!(state.board.all_cards().is_empty());
         assert_eq!(state.player_positions, [2, 3, 0, 1]);
     }
+
+    /// Prepares a showdown of Omaha Hi-Lo with the given hands, board and bets.
+    fn hi_lo_showdown(hands: &[&str], board: &str, bets: &[ChipCount]) -> TransparentState {
+        let mut state = TransparentState::new(1, hands.len() - 1, vec![100; hands.len()]);
+        state.variant = GameVariant::OmahaHiLo;
+        for (pos, hand) in hands.iter().enumerate() {
+            state.hands[pos] = CardCollection::try_from(*hand).unwrap().to_vec();
+            state.pot.place_chips(pos, bets[pos]);
+            state.player_stacks[pos] -= bets[pos];
+        }
+        let board = CardCollection::try_from(board).unwrap();
+        state.deal_flop([board[0], board[1], board[2]]);
+        state.deal_turn(board[3]);
+        state.deal_river(board[4]);
+        state
+    }
+
+    #[test]
+    fn test_hi_lo_split_pot() {
+        let mut state = hi_lo_showdown(
+            &["KcQhQdTs", "Ac3h9s9d", "JcJdTcTd"],
+            "2c4d7hKsKd",
+            &[10, 10, 11],
+        );
+        state.player_positions.retain(|&pos| pos != 2);
+
+        // the odd chip goes to the high hand
+        assert_eq!(state.end_round(), Action::Win(vec![(0, 16), (1, 15)]));
+        assert_eq!(state.player_stacks, vec![106, 105, 89]);
+    }
+
+    #[test]
+    fn test_award_run_splits_odd_chips() {
+        let mut state = hi_lo_showdown(&["KcQhQdTs", "Ac3h9s9d"], "2c4d7hKsKd", &[10, 11]);
+        state.variant = GameVariant::Omaha;
+        let side_pots = state.take_side_pots();
+        assert_eq!(side_pots, vec![(20, vec![0, 1]), (1, vec![1])]);
+
+        // the odd chips go to the first run
+        assert_eq!(
+            state.award_run(0, 3, &side_pots),
+            Action::Run(0, Box::new(Action::Win(vec![(0, 8), (1, 1)])))
+        );
+        assert_eq!(
+            state.award_run(1, 3, &side_pots),
+            Action::Run(1, Box::new(Action::Win(vec![(0, 6)])))
+        );
+        assert_eq!(state.player_stacks, vec![104, 90]);
+    }
+
+    #[test]
+    fn test_hi_lo_quartered_low() {
+        let mut state = hi_lo_showdown(&["Ac3hKcQh", "Ad3s9s9d"], "2c4d7hKsKd", &[20, 20]);
+
+        assert_eq!(state.end_round(), Action::Win(vec![(0, 30), (1, 10)]));
+    }
+
+    #[test]
+    fn test_hi_lo_without_qualifying_low() {
+        let mut state = hi_lo_showdown(&["Ac3hKcQh", "Ad3s8s8d"], "2c9hJdKsKd", &[20, 20]);
+
+        assert_eq!(state.end_round(), Action::Win(vec![(0, 40)]));
+    }
+
+    #[test]
+    fn test_hi_lo_side_pot() {
+        // player 1 is all-in for the main pot only
+        let mut state = hi_lo_showdown(
+            &["KcQhQdTs", "Ac3h9s9d", "As2s9hTc"],
+            "4c5d7hKsKd",
+            &[50, 10, 50],
+        );
+
+        // main pot: high 15 to player 0, low 15 to player 2 (A-2 beats A-3)
+        // side pot: high 40 to player 0, low 40 to player 2
+        assert_eq!(state.end_round(), Action::Win(vec![(0, 55), (2, 55)]));
+        assert_eq!(state.player_stacks, vec![105, 90, 105]);
+    }
 }
@@ -0,0 +1,273 @@
+//! This module exposes a round which pauses whenever a player has to decide, such that the
+//! decisions can be taken outside of the round, i. e. in a GUI event loop or by a network server.
+use crate::actions::{Action, PlayerAction};
+use crate::play::{Round, RoundCheckpoint};
+use crate::player::Player;
+use crate::state::TransparentState;
+use crate::ChipCount;
+
+use std::fmt;
+use std::ops::DerefMut;
+
+/// A step taken by a `SteppedRound`.
+#[derive(Debug, Clone)]
+pub enum Step<'s> {
+    /// The round progressed by the given action.
+    Action(Action),
+    /// The player at the given position has to choose one of the possible actions. The round
+    /// does not progress until the decision is applied using `SteppedRound::apply`.
+    NeedsDecision {
+        /// The position of the player who has to decide.
+        position: usize,
+        /// The state of the round as seen by the player.
+        view: &'s TransparentState,
+        /// The actions the player may choose from, see `Player::act`.
+        possible_actions: Vec<PlayerAction>,
+    },
+    /// The round ended.
+    Finished,
+}
+
+/// The error returned by `SteppedRound::apply` if the decision is not one of the possible actions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidDecision {
+    /// The position of the player who decided.
+    pub position: usize,
+    /// The decision which was rejected.
+    pub action: PlayerAction,
+}
+
+impl fmt::Display for InvalidDecision {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Player {} may not take action {:?}",
+            self.position, self.action
+        )
+    }
+}
+
+impl std::error::Error for InvalidDecision {}
+
+/// A player whose decisions are taken outside of the round, see `SteppedRound`.
+///
+/// The player posts forced bets, declines straddles and runs the board once. It panics if it is
+/// asked to decide, i. e. if a round is not played step by step.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ExternalPlayer;
+
+impl Player for ExternalPlayer {
+    fn init(&mut self, _position: usize, _initial_stack: ChipCount) {}
+
+    fn act(
+        &mut self,
+        _state: &TransparentState,
+        possible_actions: &[PlayerAction],
+    ) -> PlayerAction {
+        match possible_actions {
+            [action] => *action,
+            _ => panic!("External players should only decide in stepped rounds"),
+        }
+    }
+
+    fn bust(&mut self) {}
+}
+
+/// A round which yields the decisions it requires instead of asking the players.
+///
+/// Decisions are requested by `Step::NeedsDecision` and applied using `apply`. The players are
+/// still asked for everything else, i. e. whether to straddle, see `ExternalPlayer` for players
+/// which do not need to be implemented.
+pub struct SteppedRound<'a, P: Player, T: DerefMut<Target = TransparentState>> {
+    round: Round<'a, P, T>,
+}
+
+impl<'a, P: Player, T: DerefMut<Target = TransparentState>> SteppedRound<'a, P, T> {
+    pub(crate) fn new(round: Round<'a, P, T>) -> Self {
+        Self {
+            round: round.with_external_decisions(),
+        }
+    }
+
+    /// Progresses the state of the round one step ahead, unless a decision is requested.
+    ///
+    /// The same decision is requested until it is applied. All actions taken so far are mirrored
+    /// into the underlying `TransparentState`.
+    pub fn step(&mut self) -> Step<'_> {
+        if let Some(action) = self.round.next() {
+            return Step::Action(action);
+        }
+        match self.round.next_to_act() {
+            Some(position) => {
+                let state = self.round.inspect_state();
+                Step::NeedsDecision {
+                    position,
+                    view: state,
+                    possible_actions: state.possible_actions(position),
+                }
+            }
+            None => Step::Finished,
+        }
+    }
+
+    /// Applies the decision of the player at the given position, which is taken in the next step.
+    ///
+    /// Decisions which are not among the possible actions are rejected, the decision is still
+    /// requested then. Panics if the player was not asked to decide.
+    pub fn apply(&mut self, position: usize, action: PlayerAction) -> Result<(), InvalidDecision> {
+        assert_eq!(
+            self.round.next_to_act(),
+            Some(position),
+            "Only the player asked for a decision should act"
+        );
+        let possible_actions = self.round.inspect_state().possible_actions(position);
+        if !action.is_allowed(&possible_actions) {
+            return Err(InvalidDecision { position, action });
+        }
+        self.round.decide(action);
+        Ok(())
+    }
+
+    /// Clones this state into a `RoundCheckpoint`, see `Round::create_checkpoint`.
+    pub fn create_checkpoint(&self) -> RoundCheckpoint {
+        self.round.create_checkpoint()
+    }
+
+    /// Inspect the underlying `TransparentState` before stepping to the next action.
+    pub fn inspect_state(&self) -> &'_ TransparentState {
+        self.round.inspect_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::CardCollection;
+    use crate::table::{BlindPolicy, Table};
+
+    #[test]
+    fn test_play_stepped_round() {
+        let players = vec![ExternalPlayer; 3];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+        let mut round = table
+            .play_one_round_stepwise(CardCollection::default())
+            .unwrap();
+        let mut decisions = vec![
+            (0, PlayerAction::Raise(10)),
+            (1, PlayerAction::Fold),
+            (2, PlayerAction::Fold),
+        ]
+        .into_iter();
+
+        let mut actions = Vec::new();
+        loop {
+            match round.step() {
+                Step::Action(action) => actions.push(action),
+                Step::NeedsDecision {
+                    position,
+                    possible_actions,
+                    ..
+                } => {
+                    let (expected, decision) = decisions.next().unwrap();
+                    assert_eq!(position, expected);
+                    assert!(possible_actions.contains(&PlayerAction::Fold));
+                    round.apply(position, decision).unwrap();
+                }
+                Step::Finished => break,
+            }
+        }
+
+        assert_eq!(
+            actions[4..],
+            [
+                Action::Blind(1, 1),
+                Action::Blind(2, 2),
+                Action::Raise(0, 10),
+                Action::Fold(1),
+                Action::Fold(2),
+                Action::Win(vec![(0, 13)]),
+            ]
+        );
+        assert_eq!(round.inspect_state().actions, actions);
+    }
+
+    #[test]
+    fn test_decision_requested_until_applied() {
+        let players = vec![ExternalPlayer; 2];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+        let mut round = table
+            .play_one_round_stepwise(CardCollection::default())
+            .unwrap();
+        while let Step::Action(_) = round.step() {}
+
+        for _ in 0..2 {
+            match round.step() {
+                Step::NeedsDecision {
+                    position,
+                    view,
+                    possible_actions,
+                } => {
+                    assert_eq!(position, 1);
+                    assert_eq!(view.pot.total_size(), 3);
+                    assert_eq!(
+                        possible_actions,
+                        [
+                            PlayerAction::AllIn(99),
+                            PlayerAction::Fold,
+                            PlayerAction::Call(1),
+                            PlayerAction::Raise(3),
+                        ]
+                    );
+                }
+                step => panic!("Unexpected step {:?}", step),
+            }
+        }
+        round.apply(1, PlayerAction::Call(1)).unwrap();
+        assert!(matches!(round.step(), Step::Action(Action::Call(1, 1))));
+    }
+
+    #[test]
+    fn test_reject_invalid_decision() {
+        let players = vec![ExternalPlayer; 2];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+        let mut round = table
+            .play_one_round_stepwise(CardCollection::default())
+            .unwrap();
+        while let Step::Action(_) = round.step() {}
+
+        // the small blind faces the big blind and may neither check nor call a wrong amount
+        for action in [
+            PlayerAction::Check,
+            PlayerAction::Call(5),
+            PlayerAction::Raise(2),
+            PlayerAction::Blind(1),
+        ] {
+            assert_eq!(
+                round.apply(1, action),
+                Err(InvalidDecision {
+                    position: 1,
+                    action
+                })
+            );
+            assert!(matches!(
+                round.step(),
+                Step::NeedsDecision { position: 1, .. }
+            ));
+        }
+
+        // raises above the minimum are fine
+        round.apply(1, PlayerAction::Raise(5)).unwrap();
+        assert!(matches!(round.step(), Step::Action(Action::Raise(1, 5))));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_apply_out_of_turn() {
+        let players = vec![ExternalPlayer; 2];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+        let mut round = table
+            .play_one_round_stepwise(CardCollection::default())
+            .unwrap();
+        let _ = round.apply(0, PlayerAction::Fold);
+    }
+}
@@ -0,0 +1,360 @@
+use crate::actions::Action;
+use crate::deck::{Card, Deck};
+use crate::observer::{self, TableObserver};
+use crate::player::{Player, Seats};
+use crate::state::{BetRoundState, TransparentState};
+use std::ops::DerefMut;
+
+/// This enum represents the current stage of a stud round.
+/// It is used for the `StudRound` structure to hold state information
+#[derive(Debug, Clone)]
+enum StudIteratorStage {
+    /// The round is about to start
+    Init,
+    /// The player at the given index of the active players is about to receive the cards dealt
+    /// face down on third street
+    DealHand(usize),
+    /// The player at the given index of the active players is about to pay the ante.
+    Ante(usize),
+    /// The player at the given index of the active players is about to receive a card face up
+    DealUp(usize),
+    /// The player at the given index of the active players is about to receive the last card
+    /// face down
+    DealDown(usize),
+    /// The player showing the lowest up card is about to bring in.
+    BringIn,
+    /// A betting round is in progress. Eventually it ends when either no player is remaining
+    /// (skips ahead to distribute the pot) or the next street is dealt.
+    Betting(BetRoundState),
+    /// The past-end stage, indicating that this round is finished. This stage will loop indefinitely.
+    PastEnd,
+}
+
+/// Structure to wrap the `TransparentState` into an iterator playing a round of a stud game.
+/// Each step taken by the iterator corresponds to one step taken in the round played.
+///
+/// This is the counterpart of `Round` for games without community cards, see
+/// `GameVariant::SevenCardStud`. The cards dealt face up are available to all players using
+/// `TransparentState::up_cards`.
+pub struct StudRound<'a, P: Player, T: DerefMut<Target = TransparentState>> {
+    players: Seats<'a, P>,
+    observers: &'a mut [Box<dyn TableObserver>],
+    // whether players bust at the end of the round, see `with_busts`
+    busts: bool,
+    transparent_state: T,
+    next_cards: Vec<Card>,
+    iterator_stage: StudIteratorStage,
+}
+
+impl<'a, P: Player, T: DerefMut<Target = TransparentState>> StudRound<'a, P, T> {
+    pub(crate) fn new(
+        players: &'a mut [Option<P>],
+        mut transparent_state: T,
+        mut deck: impl Deck,
+    ) -> Self {
+        transparent_state.reset();
+        transparent_state.prepare_hands(&mut deck);
+
+        // we pre-emptively reserve the cards of the following streets, each player receives five more cards
+        let num_cards = transparent_state.num_players() * 5;
+        let mut next_cards = Vec::with_capacity(num_cards);
+        for _ in 0..num_cards {
+            next_cards.push(deck.deal().expect("Deck should contain enough cards"));
+        }
+        // we will want to preserve order, just for consistency reasons (since we will be popping from back to front)
+        next_cards.reverse();
+
+        Self {
+            players: Seats::new(players),
+            observers: &mut [],
+            busts: false,
+            transparent_state,
+            next_cards,
+            iterator_stage: StudIteratorStage::Init,
+        }
+    }
+
+    /// Inspect the underlying `TransparentState` before stepping to the next action.
+    pub fn inspect_state(&self) -> &'_ TransparentState {
+        &self.transparent_state
+    }
+
+    /// Notifies the given observers of all the following actions.
+    pub(crate) fn with_observers(mut self, observers: &'a mut [Box<dyn TableObserver>]) -> Self {
+        self.observers = observers;
+        self
+    }
+
+    /// Whether the players who lost all their chips bust once the round ended, see
+    /// `Player::bust`.
+    pub(crate) fn with_busts(mut self, busts: bool) -> Self {
+        self.busts = busts;
+        self
+    }
+
+    fn end_round(&mut self) -> Action {
+        self.iterator_stage = StudIteratorStage::PastEnd;
+        self.transparent_state.end_round()
+    }
+
+    /// The stage following the betting round of the current street.
+    fn next_street(&mut self) -> Option<Action> {
+        let state = &self.transparent_state;
+        let num_cards = state.hands[state.player_positions[0]].len();
+        if state.num_players() == 1 || num_cards == 7 {
+            Some(self.end_round())
+        } else if num_cards == 6 {
+            self.iterator_stage = StudIteratorStage::DealDown(0);
+            self.step()
+        } else {
+            self.iterator_stage = StudIteratorStage::DealUp(0);
+            self.step()
+        }
+    }
+
+    /// The betting round after all cards of a street have been dealt, starting with the player
+    /// showing the best hand. This is used from fourth street onwards.
+    fn betting_stage(&self) -> StudIteratorStage {
+        let state = &self.transparent_state;
+        StudIteratorStage::Betting(state.init_stud_action(state.best_showing_index(), None))
+    }
+}
+
+impl<'a, P: Player, T: DerefMut<Target = TransparentState>> StudRound<'a, P, T> {
+    /// Progresses the state of the round one step ahead, see `next`.
+    fn step(&mut self) -> Option<Action> {
+        let num_players = self.transparent_state.num_players();
+        match &mut self.iterator_stage {
+            StudIteratorStage::Init => {
+                self.iterator_stage = StudIteratorStage::DealHand(0);
+                Some(self.transparent_state.start_round())
+            }
+            StudIteratorStage::DealHand(i) => {
+                let i = *i;
+                self.iterator_stage = if i + 1 < num_players {
+                    StudIteratorStage::DealHand(i + 1)
+                } else if self.transparent_state.ante > 0 {
+                    StudIteratorStage::Ante(0)
+                } else {
+                    StudIteratorStage::DealUp(0)
+                };
+                Some(self.transparent_state.deal_hand(i))
+            }
+            StudIteratorStage::Ante(i) => {
+                let i = *i;
+                self.iterator_stage = if i + 1 < num_players {
+                    StudIteratorStage::Ante(i + 1)
+                } else {
+                    StudIteratorStage::DealUp(0)
+                };
+                Some(self.transparent_state.apply_ante(i, &mut self.players))
+            }
+            StudIteratorStage::DealUp(i) => {
+                let i = *i;
+                let card = self.next_cards.pop().unwrap();
+                let action = self.transparent_state.deal_up(i, card);
+                let third_street = self.transparent_state.up_cards
+                    [self.transparent_state.player_positions[i]]
+                    .len()
+                    == 1;
+                self.iterator_stage = if i + 1 < num_players {
+                    StudIteratorStage::DealUp(i + 1)
+                } else if !third_street {
+                    self.betting_stage()
+                } else if self.transparent_state.blind_size > 0 {
+                    StudIteratorStage::BringIn
+                } else {
+                    // without a bring-in the player showing the lowest card opens
+                    let i = self.transparent_state.bring_in_index();
+                    StudIteratorStage::Betting(self.transparent_state.init_stud_action(i, None))
+                };
+                Some(action)
+            }
+            StudIteratorStage::DealDown(i) => {
+                let i = *i;
+                self.iterator_stage = if i + 1 < num_players {
+                    StudIteratorStage::DealDown(i + 1)
+                } else {
+                    self.betting_stage()
+                };
+                let card = self.next_cards.pop().unwrap();
+                Some(self.transparent_state.deal_down(i, card))
+            }
+            StudIteratorStage::BringIn => {
+                let i = self.transparent_state.bring_in_index();
+                let position = self.transparent_state.player_positions[i];
+                self.iterator_stage = StudIteratorStage::Betting(
+                    self.transparent_state
+                        .init_stud_action((i + 1) % num_players, Some(position)),
+                );
+                Some(self.transparent_state.apply_bring_in(i, &mut self.players))
+            }
+            StudIteratorStage::Betting(i) => {
+                while !i.done() {
+                    let action = self.transparent_state.step_bet_round(i, &mut self.players);
+                    if action.is_some() {
+                        return action;
+                    }
+                }
+
+                self.next_street()
+            }
+            StudIteratorStage::PastEnd => None,
+        }
+    }
+}
+
+impl<'a, P: Player, T: DerefMut<Target = TransparentState>> Iterator for StudRound<'a, P, T> {
+    type Item = Action;
+
+    /// Progresses the state of the round one step ahead.
+    /// All actions taken so far are mirrored into the underlying `TransparentState`
+    fn next(&mut self) -> Option<Self::Item> {
+        let action = self.step()?;
+        let round_ended = matches!(self.iterator_stage, StudIteratorStage::PastEnd);
+        observer::notify_players(
+            &mut self.players,
+            &action,
+            &self.transparent_state,
+            round_ended,
+        );
+        observer::notify(
+            self.observers,
+            &action,
+            &self.transparent_state,
+            round_ended,
+        );
+        if round_ended && self.busts {
+            observer::bust_players(
+                &mut self.players,
+                self.observers,
+                &mut self.transparent_state,
+            );
+        }
+        Some(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::PlayerAction;
+    use crate::deck::CardCollection;
+    use crate::mock::MockPlayer;
+    use crate::{BettingStructure, ChipCount, GameVariant};
+    use std::convert::TryFrom;
+
+    /// Creates a deck which deals the given cards in order.
+    fn deck(cards: &str) -> CardCollection {
+        let mut cards = CardCollection::try_from(cards).unwrap().to_vec();
+        cards.reverse();
+        CardCollection::from(cards)
+    }
+
+    fn card(card: &str) -> Card {
+        CardCollection::try_from(card).unwrap()[0]
+    }
+
+    fn stud_state(stacks: Vec<u32>) -> TransparentState {
+        // the dealer moves to the first position once the round starts
+        let mut state = TransparentState::new(1, stacks.len() - 1, stacks);
+        state.variant = GameVariant::SevenCardStud;
+        state
+    }
+
+    #[test]
+    fn test_bring_in_and_best_showing_hand_acts_first() {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::BringIn(1), PlayerAction::Fold]), // dealer
+            MockPlayer::new(vec![PlayerAction::Raise(2), PlayerAction::Bet(2)]),
+            MockPlayer::new(vec![PlayerAction::Call(2), PlayerAction::Fold]),
+        ];
+        let mut state = stud_state(vec![100, 100, 100]);
+        let deck = deck("AhAdKhKd2c3c9s4d4c9hKs5c6c7c8c9cTcJcQcKcAc");
+        let mut players: Vec<_> = players.into_iter().map(Some).collect();
+        let mut round = StudRound::new(&mut players, &mut state, deck);
+        let mut actions: Vec<Action> = (&mut round).take(8).collect();
+        // the bring-in may be completed to a full bet
+        assert_eq!(
+            round.inspect_state().bet_limits(1),
+            Some((2, ChipCount::MAX))
+        );
+        actions.extend(round);
+
+        assert_eq!(
+            actions[4..],
+            [
+                Action::DealUp(1, card("9s")),
+                Action::DealUp(2, card("4d")),
+                // clubs are the lowest suit
+                Action::DealUp(0, card("4c")),
+                Action::BringIn(0, 1),
+                Action::Raise(1, 2),
+                Action::Call(2, 2),
+                Action::Fold(0),
+                Action::DealUp(1, card("9h")),
+                Action::DealUp(2, card("Ks")),
+                // the pair of nines showing acts first
+                Action::Bet(1, 2),
+                Action::Fold(2),
+                Action::Win(vec![(1, 7)]),
+            ]
+        );
+        assert_eq!(state.up_cards[1], [card("9s"), card("9h")]);
+        assert_eq!(state.query_cards(1).len(), 4);
+    }
+
+    #[test]
+    fn test_play_fixed_limit_to_showdown() {
+        let players = vec![
+            MockPlayer::new(vec![
+                PlayerAction::Call(1),
+                PlayerAction::Bet(2),
+                PlayerAction::Bet(4),
+                PlayerAction::Check,
+                PlayerAction::Check,
+            ]), // dealer
+            MockPlayer::new(vec![
+                PlayerAction::BringIn(1),
+                PlayerAction::Call(2),
+                PlayerAction::Call(4),
+                PlayerAction::Check,
+                PlayerAction::Check,
+            ]),
+        ];
+        let mut state = stud_state(vec![100, 100]);
+        state.betting_structure = BettingStructure::FixedLimit { raise_cap: 4 };
+        let deck = deck("AhAdKcKd2s3c7h8d9cJsTh4sAcKh");
+        let mut players: Vec<_> = players.into_iter().map(Some).collect();
+        let actions: Vec<Action> = StudRound::new(&mut players, &mut state, deck).collect();
+
+        // the bring-in does not get the option to raise
+        assert_eq!(
+            actions[5..8],
+            [
+                Action::BringIn(1, 1),
+                Action::Call(0, 1),
+                Action::DealUp(1, card("7h")),
+            ]
+        );
+        assert_eq!(
+            actions[actions.len() - 5..],
+            [
+                Action::DealDown(1, card("Ac")),
+                Action::DealDown(0, card("Kh")),
+                Action::Check(0),
+                Action::Check(1),
+                Action::Win(vec![(1, 14)]),
+            ]
+        );
+        // big bets from fifth street onwards
+        assert!(players[0]
+            .as_ref()
+            .unwrap()
+            .last_possible_actions
+            .contains(&PlayerAction::Bet(4)));
+        assert_eq!(state.up_cards[0].len(), 4);
+        assert_eq!(state.query_cards(0).len(), 7);
+        assert_eq!(state.player_stacks, [93, 107]);
+    }
+}
@@ -0,0 +1,56 @@
+//! This module exposes the summary of a finished round, see `Player::round_finished`.
+use crate::actions::Action;
+use crate::deck::Card;
+use crate::state::TransparentState;
+use crate::ChipCount;
+
+/// The outcome of a finished round.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundSummary {
+    /// The chips each player won or lost in the round, indexed by player position.
+    pub net_results: Vec<i64>,
+
+    /// The hands revealed at showdown by the players who were still in the round, in order of
+    /// their positions. This is empty if all but one player folded.
+    ///
+    /// Players do not observe these hands as actions, see `Player::observe`.
+    pub showdown: Vec<(usize, Vec<Card>)>,
+
+    /// The stacks of all the players after the round, indexed by player position.
+    pub final_stacks: Vec<ChipCount>,
+}
+
+impl RoundSummary {
+    /// Summarizes the round which just ended in the given state.
+    pub(crate) fn new(state: &TransparentState) -> Self {
+        let final_stacks = state.player_stacks.clone();
+        let initial_stacks = state
+            .actions
+            .iter()
+            .find_map(|action| match action {
+                Action::StartRound { player_stacks, .. } => Some(player_stacks),
+                _ => None,
+            })
+            .unwrap_or(&final_stacks);
+        let net_results = final_stacks
+            .iter()
+            .zip(initial_stacks)
+            .map(|(&stack, &initial)| stack as i64 - initial as i64)
+            .collect();
+
+        let mut showdown = Vec::new();
+        if state.num_players() > 1 {
+            let mut positions = state.player_positions.clone();
+            positions.sort_unstable();
+            for pos in positions {
+                showdown.push((pos, state.hands[pos].clone()));
+            }
+        }
+
+        Self {
+            net_results,
+            showdown,
+            final_stacks,
+        }
+    }
+}
@@ -1,7 +1,14 @@
+use crate::asynchronous::{AsyncPlayer, AsyncRound, AsyncSeat};
+use crate::betting::{BettingStructure, MissedBlinds, Straddle};
 use crate::deck::Deck;
+use crate::observer::{self, TableObserver};
 use crate::play::{Round, RoundCheckpoint};
-use crate::player::Player;
+use crate::player::{Player, Seats};
 use crate::state::{CheckpointState, TransparentState};
+use crate::stepped::SteppedRound;
+use crate::stud::StudRound;
+use crate::timing::{TimedPlayer, TimedRound};
+use crate::variant::GameVariant;
 use crate::ChipCount;
 
 /// Exposes variants to handle blind policies, i. e. control when and how much the blind size should be increased.
@@ -10,12 +17,61 @@ pub enum BlindPolicy {
     NeverIncrease,
 }
 
+/// Options which apply to a single round only, see `Table::play_one_round_with`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RoundOptions {
+    /// Play a bomb pot, i. e. every player posts the given amount instead of the ante, the
+    /// blinds and the pre-flop betting are skipped and the action starts on the flop.
+    ///
+    /// Players who do not cover the amount post their whole stack.
+    pub bomb_pot: Option<ChipCount>,
+    /// Play this round on two boards, see `Table::with_double_board`.
+    pub double_board: bool,
+}
+
+/// The rules of a cash game, see `Table::with_cash_game`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CashGame {
+    /// The minimum number of chips a player may buy in for.
+    pub min_buy_in: ChipCount,
+    /// The maximum number of chips a player may buy in for, which caps rebuys and top-ups.
+    pub max_buy_in: ChipCount,
+    /// The stack every player below this size is automatically topped up to between rounds,
+    /// if any.
+    pub top_up: Option<ChipCount>,
+}
+
+/// The chips a player bought in for and cashed out at a table, see `Table::ledger`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LedgerEntry {
+    /// The total number of chips the player bought in for, including the initial stack.
+    pub bought_in: ChipCount,
+    /// The total number of chips the player took from the table, see `Table::cash_out`.
+    pub cashed_out: ChipCount,
+}
+
+impl LedgerEntry {
+    /// The net result of the player, i. e. the chips cashed out minus the chips bought in.
+    pub fn net(&self) -> i64 {
+        self.cashed_out as i64 - self.bought_in as i64
+    }
+}
+
 /// The main entrypoint for playing poker games.
 /// A table represents a collection of players and handles the actual game logic.
 pub struct Table<P> {
-    players: Vec<P>,
+    // empty seats are `None`
+    players: Vec<Option<P>>,
+    // there is only `BlindPolicy::NeverIncrease` for now, so this is never evaluated
+    #[allow(dead_code)]
     blind_policy: BlindPolicy,
     transparent_state: TransparentState,
+    double_board: bool,
+    cash_game: Option<CashGame>,
+    ledger: Vec<LedgerEntry>,
+    // players who left the table or the cash game are not offered to buy in anymore
+    left: Vec<bool>,
+    observers: Vec<Box<dyn TableObserver>>,
 }
 
 impl<P: Player> Table<P> {
@@ -35,17 +91,279 @@ impl<P: Player> Table<P> {
         blind_size: ChipCount,
         blind_policy: BlindPolicy,
     ) -> Self {
-        let players: Vec<P> = players.collect();
+        let players: Vec<Option<P>> = players.map(Some).collect();
         assert!(players.len() < 20);
         assert!(players.len() > 1);
 
         let stack_sizes = vec![stack_size; players.len()];
         let dealer_position = players.len() - 1;
+        let ledger = vec![
+            LedgerEntry {
+                bought_in: stack_size,
+                cashed_out: 0,
+            };
+            players.len()
+        ];
+        let left = vec![false; players.len()];
 
         Self {
             players,
             blind_policy,
             transparent_state: TransparentState::new(blind_size, dealer_position, stack_sizes),
+            double_board: false,
+            cash_game: None,
+            ledger,
+            left,
+            observers: Vec::new(),
+        }
+    }
+
+    /// Select the betting structure used for all the following rounds.
+    ///
+    /// By default `BettingStructure::NoLimit` is used.
+    pub fn with_betting_structure(mut self, betting_structure: BettingStructure) -> Self {
+        self.transparent_state.betting_structure = betting_structure;
+        self
+    }
+
+    /// Select the size of the ante each player pays before the blinds in all the following rounds.
+    ///
+    /// By default there are no antes. Together with a blind size of zero this allows to play
+    /// ante-only games, in which the minimum bet is equal to the ante.
+    pub fn with_ante(mut self, ante: ChipCount) -> Self {
+        self.transparent_state.ante = ante;
+        self
+    }
+
+    /// Offer a straddle of twice the big blind in all the following rounds, see `Straddle`.
+    ///
+    /// By default no straddle is offered. Whether a player straddles is decided by
+    /// `Player::straddle`.
+    pub fn with_straddle(mut self, straddle: Straddle) -> Self {
+        self.transparent_state.straddle = Some(straddle);
+        self
+    }
+
+    /// Select the rule for players returning after they missed the big blind, see `MissedBlinds`.
+    ///
+    /// By default returning players post both blinds.
+    pub fn with_missed_blinds(mut self, missed_blinds: MissedBlinds) -> Self {
+        self.transparent_state.missed_blinds = missed_blinds;
+        self
+    }
+
+    /// Register an observer which is notified of everything happening at this table from now on,
+    /// see `TableObserver`.
+    ///
+    /// Rounds replayed using `replay_one_round` are not observed.
+    pub fn with_observer(mut self, observer: impl TableObserver + 'static) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    /// Select the game variant played in all the following rounds.
+    ///
+    /// By default `GameVariant::Holdem` is used.
+    ///
+    /// Rounds have to be played with the deck of the variant, see `GameVariant::deck`.
+    ///
+    /// Panics if the deck does not contain enough cards to deal the hole cards of every
+    /// player and the board, e. g. for more than 11 players in Omaha or more than 7 players in
+    /// seven-card stud.
+    pub fn with_variant(mut self, variant: GameVariant) -> Self {
+        let second_board = if self.double_board { 5 } else { 0 };
+        assert!(variant.cards_required(self.players.len()) + second_board <= variant.deck().len());
+
+        self.transparent_state.variant = variant;
+        self
+    }
+
+    /// Play all the following rounds on two boards, which are dealt simultaneously.
+    ///
+    /// The hands are ranked on each board separately and each pot is split evenly between the
+    /// boards. The rest of the board is never run multiple times in double board games.
+    ///
+    /// Panics if the deck of the game variant played does not contain enough cards for the second
+    /// board, see `with_variant`.
+    pub fn with_double_board(mut self) -> Self {
+        let variant = self.transparent_state.variant;
+        assert!(variant.cards_required(self.players.len()) + 5 <= variant.deck().len());
+
+        self.double_board = true;
+        self
+    }
+
+    /// Add empty seats to this table until there are `num_seats` seats in total, see `seat_player`.
+    ///
+    /// By default there is one seat for each player given in `Table::new`. The number of seats
+    /// has to be in the interval [2, 19] and the deck of the game variant played has to contain
+    /// enough cards for a full table, see `with_variant`.
+    pub fn with_max_seats(mut self, num_seats: usize) -> Self {
+        assert!(num_seats >= self.players.len());
+        assert!(num_seats < 20);
+        let variant = self.transparent_state.variant;
+        let second_board = if self.double_board { 5 } else { 0 };
+        assert!(variant.cards_required(num_seats) + second_board <= variant.deck().len());
+
+        self.players.resize_with(num_seats, || None);
+        self.transparent_state.add_seats(num_seats);
+        self.ledger.resize(num_seats, LedgerEntry::default());
+        self.left.resize(num_seats, true);
+        self
+    }
+
+    /// Seat the given player with a stack of the given size at the empty seat with the given
+    /// position. The player is dealt in from the next round on.
+    ///
+    /// The stack is recorded as bought in in the `ledger`, adding to the entry of the players who
+    /// left the seat before.
+    ///
+    /// Panics if the seat does not exist or is not empty, see `with_max_seats`. Players who busted
+    /// or cashed out are not dealt in anymore but keep the seat until they are removed, see
+    /// `remove_player`. In a cash game the stack has to be a valid buy-in, see `with_cash_game`.
+    pub fn seat_player(&mut self, seat: usize, mut player: P, stack: ChipCount) {
+        assert!(self.players[seat].is_none(), "Seat should be empty");
+        if let Some(cash_game) = self.cash_game {
+            assert!(
+                (cash_game.min_buy_in..=cash_game.max_buy_in).contains(&stack),
+                "Stack should be a valid buy-in"
+            );
+        }
+
+        player.init(seat, stack);
+        self.players[seat] = Some(player);
+        self.transparent_state.player_stacks[seat] = stack;
+        self.transparent_state.seated[seat] = true;
+        self.ledger[seat].bought_in += stack;
+        self.left[seat] = false;
+    }
+
+    /// Remove the player seated at the given position from the table, leaving the seat empty.
+    ///
+    /// The player cashes out the whole stack, see `cash_out`. Returns `None` if the seat is
+    /// empty already.
+    pub fn remove_player(&mut self, seat: usize) -> Option<P> {
+        let player = self.players[seat].take()?;
+        self.cash_out(seat);
+        Some(player)
+    }
+
+    /// Let the player at the given seat sit out from the next round on, or return if
+    /// `sitting_out` is `false`.
+    ///
+    /// Players sitting out keep their seat and stack but are not dealt in. Between rounds players
+    /// may change their mind using `Player::sit_out`. Players who miss the big blind while sitting
+    /// out have to make up for it when they return, see `with_missed_blinds`.
+    pub fn set_sitting_out(&mut self, seat: usize, sitting_out: bool) {
+        assert!(
+            self.players[seat].is_some(),
+            "Only seated players can sit out"
+        );
+        self.transparent_state.sitting_out[seat] = sitting_out;
+    }
+
+    /// Play a cash game with the given rules at this table.
+    ///
+    /// Between rounds, players who lost all their chips are offered to buy in again (see
+    /// `Player::rebuy`) and stacks below `CashGame::top_up` are topped up automatically. All
+    /// buy-ins are recorded in the `ledger`.
+    ///
+    /// The stacks of the players seated already have to be valid buy-ins.
+    pub fn with_cash_game(mut self, cash_game: CashGame) -> Self {
+        assert!(cash_game.min_buy_in <= cash_game.max_buy_in);
+        assert!(cash_game
+            .top_up
+            .map(|top_up| top_up <= cash_game.max_buy_in)
+            .unwrap_or(true));
+        let buy_ins = cash_game.min_buy_in..=cash_game.max_buy_in;
+        for (seat, player) in self.players.iter().enumerate() {
+            assert!(
+                player.is_none() || buy_ins.contains(&self.transparent_state.player_stacks[seat]),
+                "Stacks should be valid buy-ins"
+            );
+        }
+
+        self.cash_game = Some(cash_game);
+        self
+    }
+
+    /// The chips each player bought in for and cashed out so far, indexed by player position.
+    ///
+    /// The entry of a seat which was taken by several players adds up all of them, see
+    /// `seat_player`.
+    pub fn ledger(&self) -> &[LedgerEntry] {
+        &self.ledger
+    }
+
+    /// The player at the given position leaves the cash game, taking the whole stack.
+    ///
+    /// The stack is recorded as cashed out in the `ledger`, the seat is not dealt in and the
+    /// player is not offered to buy in anymore. Returns the number of chips cashed out.
+    pub fn cash_out(&mut self, position: usize) -> ChipCount {
+        let stack = std::mem::replace(&mut self.transparent_state.player_stacks[position], 0);
+        self.ledger[position].cashed_out += stack;
+        self.left[position] = true;
+        self.transparent_state.empty_seat(position);
+        stack
+    }
+
+    /// Prepares the next round, i. e. the buy-ins of a cash game.
+    ///
+    /// Returns `false` if less than two players are seated with chips and do not sit out.
+    fn prepare_round(&mut self) -> bool {
+        self.buy_in();
+        let state = &mut self.transparent_state;
+        for (seat, player) in Seats::new(&mut self.players).iter_mut() {
+            if state.seated[seat] {
+                state.sitting_out[seat] = player.sit_out(state, state.sitting_out[seat]);
+            }
+        }
+        let num_active = (0..self.players.len())
+            .filter(|&seat| state.is_active(seat))
+            .count();
+        num_active > 1
+    }
+
+    /// Offers rebuys to busted players and tops up short stacks before a round of a cash game.
+    fn buy_in(&mut self) {
+        let cash_game = match self.cash_game {
+            Some(cash_game) => cash_game,
+            None => return,
+        };
+
+        let mut players = Seats::new(&mut self.players);
+        for position in 0..self.left.len() {
+            // empty seats are marked as left as well
+            if self.left[position] {
+                continue;
+            }
+            let state = &self.transparent_state;
+            let stack = state.player_stacks[position];
+            let buy_in = if stack == 0 {
+                let rebuy =
+                    players[position].rebuy(state, cash_game.min_buy_in, cash_game.max_buy_in);
+                match rebuy {
+                    Some(rebuy) => rebuy.clamp(cash_game.min_buy_in, cash_game.max_buy_in),
+                    None => {
+                        self.left[position] = true;
+                        observer::bust(
+                            &mut players[position],
+                            &mut self.observers,
+                            position,
+                            &mut self.transparent_state,
+                        );
+                        continue;
+                    }
+                }
+            } else {
+                match cash_game.top_up {
+                    Some(top_up) if stack < top_up => top_up - stack,
+                    _ => continue,
+                }
+            };
+
+            self.transparent_state.player_stacks[position] += buy_in;
+            self.ledger[position].bought_in += buy_in;
         }
     }
 
@@ -54,8 +372,75 @@ impl<P: Player> Table<P> {
     /// Returns a `Round` structure which is essentially a fancy iterator.
     ///
     /// It is expected that the given deck is valid, i. e. contains all cards, is properly shuffled, etc.
-    pub fn play_one_round(&mut self, deck: impl Deck) -> Round<'_, P, &mut TransparentState> {
-        Round::new(&mut self.players, &mut self.transparent_state, deck)
+    ///
+    /// Returns `None` if less than two players are left to play the round, i. e. all other players
+    /// busted or sit out.
+    ///
+    /// Panics if a stud game is played at this table, see `play_one_stud_round`.
+    pub fn play_one_round(
+        &mut self,
+        deck: impl Deck,
+    ) -> Option<Round<'_, P, &mut TransparentState>> {
+        self.play_one_round_with(deck, RoundOptions::default())
+    }
+
+    /// Play one round of poker at this table using the given deck and the given options, which
+    /// only apply to this round.
+    ///
+    /// See `play_one_round` for details.
+    pub fn play_one_round_with(
+        &mut self,
+        deck: impl Deck,
+        options: RoundOptions,
+    ) -> Option<Round<'_, P, &mut TransparentState>> {
+        assert!(!self.transparent_state.variant.is_stud());
+        if !self.prepare_round() {
+            return None;
+        }
+        self.transparent_state.bomb_pot = options.bomb_pot;
+        self.transparent_state.double_board = self.double_board || options.double_board;
+        let round = Round::new(&mut self.players, &mut self.transparent_state, deck)
+            .with_observers(&mut self.observers)
+            // players who lost their chips in a cash game are offered to buy in again instead
+            .with_busts(self.cash_game.is_none());
+        Some(round)
+    }
+
+    /// Play one round of poker at this table using the given deck, the round pauses whenever a
+    /// player has to decide. See `SteppedRound`.
+    ///
+    /// See `play_one_round` for details.
+    pub fn play_one_round_stepwise(
+        &mut self,
+        deck: impl Deck,
+    ) -> Option<SteppedRound<'_, P, &mut TransparentState>> {
+        self.play_one_round(deck).map(SteppedRound::new)
+    }
+
+    /// Play one round of a stud game at this table using the given deck.
+    ///
+    /// Returns a `StudRound` structure which is essentially a fancy iterator.
+    ///
+    /// It is expected that the given deck is valid, i. e. contains all cards, is properly shuffled, etc.
+    ///
+    /// Returns `None` if less than two players are left to play the round, see `play_one_round`.
+    ///
+    /// Panics if the game played at this table is not a stud game, see `with_variant`.
+    pub fn play_one_stud_round(
+        &mut self,
+        deck: impl Deck,
+    ) -> Option<StudRound<'_, P, &mut TransparentState>> {
+        assert!(self.transparent_state.variant.is_stud());
+        if !self.prepare_round() {
+            return None;
+        }
+        self.transparent_state.bomb_pot = None;
+        self.transparent_state.double_board = false;
+        let round = StudRound::new(&mut self.players, &mut self.transparent_state, deck)
+            .with_observers(&mut self.observers)
+            // players who lost their chips in a cash game are offered to buy in again instead
+            .with_busts(self.cash_game.is_none());
+        Some(round)
     }
 
     /// Replay the round recovered from the given state with the players currently seated at the table.
@@ -67,6 +452,32 @@ impl<P: Player> Table<P> {
     }
 }
 
+impl<A: AsyncPlayer> Table<AsyncSeat<A>> {
+    /// Play one round of poker at this table using the given deck, the decisions of the players
+    /// are awaited.
+    ///
+    /// See `play_one_round` for details.
+    pub fn play_one_round_async(
+        &mut self,
+        deck: impl Deck,
+    ) -> Option<AsyncRound<'_, A, &mut TransparentState>> {
+        self.play_one_round(deck).map(AsyncRound::new)
+    }
+}
+
+impl<P: Player + Send + 'static> Table<TimedPlayer<P>> {
+    /// Play one round of poker at this table using the given deck, the players who do not decide
+    /// in time are recorded as `Action::Timeout`.
+    ///
+    /// See `play_one_round` for details.
+    pub fn play_one_timed_round(
+        &mut self,
+        deck: impl Deck,
+    ) -> Option<TimedRound<'_, P, &mut TransparentState>> {
+        self.play_one_round(deck).map(TimedRound::new)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -74,7 +485,10 @@ mod tests {
     use crate::deck::card::{Card, Suit, Value};
     use crate::deck::CardCollection;
     use crate::mock::MockPlayer;
-    use std::convert::TryInto;
+    use crate::summary::RoundSummary;
+    use std::cell::RefCell;
+    use std::convert::{TryFrom, TryInto};
+    use std::rc::Rc;
 
     #[test]
     #[should_panic]
@@ -101,7 +515,10 @@ mod tests {
             MockPlayer::new(vec![PlayerAction::Fold]),
         ];
         let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
-        let actions: Vec<Action> = table.play_one_round(CardCollection::default()).collect();
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
 
         // skip the meta information and card deals at the start
         assert_eq!(
@@ -131,7 +548,10 @@ mod tests {
             MockPlayer::new(vec![PlayerAction::Fold]),
         ];
         let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
-        let actions: Vec<Action> = table.play_one_round(CardCollection::default()).collect();
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
 
         // skip the meta information and card deals at the start
         assert_eq!(
@@ -181,7 +601,10 @@ mod tests {
             MockPlayer::new(vec![PlayerAction::Fold]),
         ];
         let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
-        let actions: Vec<Action> = table.play_one_round(CardCollection::default()).collect();
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
 
         // skip the meta information and card deals at the start
         assert_eq!(
@@ -242,7 +665,10 @@ mod tests {
             MockPlayer::new(vec![PlayerAction::Fold]),
         ];
         let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
-        let actions: Vec<Action> = table.play_one_round(CardCollection::default()).collect();
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
 
         assert_eq!(actions[17..19], [Action::Bet(1, 2), Action::Call(2, 2),]);
 
@@ -293,7 +719,7 @@ mod tests {
             MockPlayer::new(vec![PlayerAction::Fold]),
         ];
         let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
-        let actions: Vec<Action> = table.play_one_round(deck).collect();
+        let actions: Vec<Action> = table.play_one_round(deck).unwrap().collect();
 
         assert_eq!(
             actions[20..],
@@ -306,6 +732,85 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_round_finished_summary() {
+        // the same round as in `test_play_showdown_and_one_player_wins`
+        let deck: CardCollection = "Jc9dTcQd2s2c8cKsQsTdTh7sAd".try_into().unwrap();
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Raise(10), PlayerAction::Fold]), // dealer
+            MockPlayer::new(vec![
+                PlayerAction::Blind(1),
+                PlayerAction::Call(9),
+                PlayerAction::Check,
+                PlayerAction::Call(2),
+                PlayerAction::Bet(2),
+                PlayerAction::Bet(2),
+                PlayerAction::Call(8),
+            ]), // small
+            MockPlayer::new(vec![
+                PlayerAction::Blind(2),
+                PlayerAction::Call(8),
+                PlayerAction::Bet(2),
+                PlayerAction::Call(2),
+                PlayerAction::Raise(10),
+            ]), // big
+            MockPlayer::new(vec![PlayerAction::Fold]),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+        table.play_one_round(deck).unwrap().for_each(drop);
+
+        let card = |value, suit| Card { value, suit };
+        let expected = RoundSummary {
+            net_results: vec![-10, -24, 34, 0],
+            showdown: vec![
+                (
+                    1,
+                    vec![
+                        card(Value::Ace, Suit::Diamond),
+                        card(Value::Seven, Suit::Spade),
+                    ],
+                ),
+                (
+                    2,
+                    vec![
+                        card(Value::Ten, Suit::Heart),
+                        card(Value::Ten, Suit::Diamond),
+                    ],
+                ),
+            ],
+            final_stacks: vec![90, 76, 134, 100],
+        };
+        for player in table.players.iter() {
+            assert_eq!(player.as_ref().unwrap().summary, Some(expected.clone()));
+        }
+
+        // the dealer folded and only learns the hands shown at showdown from the summary
+        let dealer = table.players[0].as_ref().unwrap();
+        assert!(dealer
+            .observed
+            .iter()
+            .all(|action| !matches!(action, Action::DealHand(pos, _) if *pos != 0)));
+        assert_eq!(dealer.summary.as_ref().unwrap().showdown.len(), 2);
+    }
+
+    #[test]
+    fn test_round_finished_without_showdown() {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Blind(2)]), // dealer
+            MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Fold]),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+        table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .for_each(drop);
+
+        let summary = table.players[1].as_ref().unwrap().summary.clone().unwrap();
+        assert_eq!(summary.net_results, [1, -1]);
+        assert!(summary.showdown.is_empty());
+        assert_eq!(summary.final_stacks, [101, 99]);
+    }
+
     #[test]
     fn test_play_showdown_and_two_different_ranks_win() {
         // This test case covers the scenario in which an all-in player wins
@@ -343,7 +848,10 @@ mod tests {
             ]),
         ];
         let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
-        let _: Vec<_> = table.play_one_round(CardCollection::default()).collect();
+        let _: Vec<_> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
 
         // We abuse a little internal knowledge here
         // Dealing starts at the small blind, 2 cards at a time
@@ -355,7 +863,7 @@ mod tests {
         // River -> 2d
         // (eventually TT wins with triples)
         let deck: CardCollection = "2d9dTcQd2s2c8cKsQsTdTh7sAd".try_into().unwrap();
-        let actions: Vec<Action> = table.play_one_round(deck).collect();
+        let actions: Vec<Action> = table.play_one_round(deck).unwrap().collect();
 
         let flop = [
             Card {
@@ -405,6 +913,853 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_play_small_blind_folds_after_limpers() {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Call(2), PlayerAction::Fold]), // dealer
+            MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Fold]), // small
+            MockPlayer::new(vec![
+                PlayerAction::Blind(2),
+                PlayerAction::Check,
+                PlayerAction::Bet(2),
+            ]), // big
+            MockPlayer::new(vec![PlayerAction::Call(2), PlayerAction::Fold]),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+
+        // the players who limped are not asked again
+        assert_eq!(
+            actions[7..11],
+            [
+                Action::Call(3, 2),
+                Action::Call(0, 2),
+                Action::Fold(1),
+                Action::Check(2),
+            ]
+        );
+        assert!(matches!(actions[11], Action::DealFlop(_)));
+    }
+
+    #[test]
+    fn test_play_run_it_twice() {
+        let mut small = MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::AllIn(99)]);
+        small.runs = 3;
+        let mut big = MockPlayer::new(vec![PlayerAction::Blind(2), PlayerAction::AllIn(98)]);
+        big.runs = 2;
+        let players = vec![MockPlayer::new(vec![PlayerAction::Fold]), small, big];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+
+        // the deck only allows to run the board twice
+        let mut cards = CardCollection::try_from("AhAdKhKd2c7d2s3s4h8c9dKs5c6hTcJd")
+            .unwrap()
+            .to_vec();
+        cards.reverse();
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::from(cards))
+            .unwrap()
+            .collect();
+
+        let card = |card: &str| CardCollection::try_from(card).unwrap()[0];
+        let run = |run: usize, action: Action| Action::Run(run, Box::new(action));
+        assert_eq!(
+            actions[9..],
+            [
+                run(0, Action::DealFlop([card("2s"), card("3s"), card("4h")])),
+                run(0, Action::DealTurn(card("8c"))),
+                run(0, Action::DealRiver(card("9d"))),
+                run(0, Action::Win(vec![(1, 100)])),
+                run(1, Action::DealFlop([card("Ks"), card("5c"), card("6h")])),
+                run(1, Action::DealTurn(card("Tc"))),
+                run(1, Action::DealRiver(card("Jd"))),
+                run(1, Action::Win(vec![(2, 100)])),
+            ]
+        );
+        assert_eq!(table.transparent_state.player_stacks, [100, 100, 100]);
+        assert_eq!(table.transparent_state.run_boards.len(), 1);
+
+        let state = crate::replay::replay(&actions).unwrap();
+        assert_eq!(state.player_stacks, [100, 100, 100]);
+    }
+
+    #[test]
+    fn test_play_under_the_gun_straddle() {
+        let mut straddler = MockPlayer::new(vec![PlayerAction::Bet(8)]);
+        straddler.straddles = true;
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Call(4), PlayerAction::Fold]), // dealer
+            MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Fold]), // small
+            MockPlayer::new(vec![PlayerAction::Blind(2), PlayerAction::Fold]), // big
+            straddler,
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease)
+            .with_straddle(Straddle::UnderTheGun);
+        let mut round = table.play_one_round(CardCollection::default()).unwrap();
+        let mut actions: Vec<Action> = (&mut round).take(8).collect();
+        // the minimum raise is based on the straddle
+        assert_eq!(
+            round.inspect_state().bet_limits(0),
+            Some((8, ChipCount::MAX))
+        );
+        actions.extend(round);
+
+        // the straddler acts last pre-flop
+        assert_eq!(
+            actions[5..],
+            [
+                Action::Blind(1, 1),
+                Action::Blind(2, 2),
+                Action::Straddle(3, 4),
+                Action::Call(0, 4),
+                Action::Fold(1),
+                Action::Fold(2),
+                Action::Bet(3, 8),
+                Action::Fold(0),
+                Action::Win(vec![(3, 19)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_play_button_straddle() {
+        let checks = || vec![PlayerAction::Check; 3];
+        let mut dealer = MockPlayer::new(vec![PlayerAction::Check]);
+        dealer.next_actions.extend(checks());
+        dealer.straddles = true;
+        let mut small = MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Call(3)]);
+        small.next_actions.extend(checks());
+        let mut big = MockPlayer::new(vec![PlayerAction::Blind(2), PlayerAction::Call(2)]);
+        big.next_actions.extend(checks());
+        let mut under_the_gun = MockPlayer::new(vec![PlayerAction::Call(4)]);
+        under_the_gun.next_actions.extend(checks());
+        let players = vec![dealer, small, big, under_the_gun];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease)
+            .with_straddle(Straddle::Button);
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+
+        // the small blind acts first pre-flop
+        assert_eq!(
+            actions[5..12],
+            [
+                Action::Blind(1, 1),
+                Action::Blind(2, 2),
+                Action::Straddle(0, 4),
+                Action::Call(1, 3),
+                Action::Call(2, 2),
+                Action::Call(3, 4),
+                Action::Check(0),
+            ]
+        );
+        assert!(matches!(actions[12], Action::DealFlop(_)));
+
+        let state = crate::replay::replay(&actions).unwrap();
+        assert_eq!(state.player_stacks, table.transparent_state.player_stacks);
+    }
+
+    #[test]
+    fn test_straddle_not_offered_heads_up() {
+        let mut dealer = MockPlayer::new(vec![PlayerAction::Blind(2)]);
+        dealer.straddles = true;
+        let small = MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Fold]);
+        let mut table = Table::new(
+            vec![dealer, small].into_iter(),
+            100,
+            1,
+            BlindPolicy::NeverIncrease,
+        )
+        .with_straddle(Straddle::Button);
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+
+        assert_eq!(actions[5..], [Action::Fold(1), Action::Win(vec![(0, 3)])]);
+    }
+
+    #[test]
+    fn test_play_bomb_pot() {
+        let players = vec![
+            MockPlayer::new(vec![
+                PlayerAction::Ante(5),
+                PlayerAction::Fold,
+                PlayerAction::Blind(2),
+            ]), // dealer
+            MockPlayer::new(vec![
+                PlayerAction::Ante(5),
+                PlayerAction::Check,
+                PlayerAction::Fold,
+            ]), // small
+            MockPlayer::new(vec![
+                PlayerAction::Ante(5),
+                PlayerAction::Bet(10),
+                PlayerAction::Blind(1),
+            ]), // big
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+        let options = RoundOptions {
+            bomb_pot: Some(5),
+            ..RoundOptions::default()
+        };
+        let actions: Vec<Action> = table
+            .play_one_round_with(CardCollection::default(), options)
+            .unwrap()
+            .collect();
+
+        // there are neither blinds nor pre-flop betting
+        assert!(matches!(
+            actions[0],
+            Action::StartRound {
+                bomb_pot: Some(5),
+                ..
+            }
+        ));
+        assert_eq!(
+            actions[4..7],
+            [Action::Ante(1, 5), Action::Ante(2, 5), Action::Ante(0, 5)]
+        );
+        assert!(matches!(actions[7], Action::DealFlop(_)));
+        assert_eq!(
+            actions[8..],
+            [
+                Action::Check(1),
+                Action::Bet(2, 10),
+                Action::Fold(0),
+                Action::Fold(1),
+                Action::Win(vec![(2, 25)]),
+            ]
+        );
+        assert_eq!(table.transparent_state.player_stacks, [95, 95, 110]);
+
+        let state = crate::replay::replay(&actions).unwrap();
+        assert_eq!(state.player_stacks, [95, 95, 110]);
+
+        // the following rounds are played as usual
+        let mut round = table.play_one_round(CardCollection::default()).unwrap();
+        assert_eq!(round.nth(4), Some(Action::Blind(2, 1)));
+        assert_eq!(round.inspect_state().bomb_pot, None);
+    }
+
+    #[test]
+    fn test_play_double_board() {
+        let checks = || vec![PlayerAction::Check; 3];
+        let mut big = MockPlayer::new(vec![PlayerAction::Blind(2), PlayerAction::Check]);
+        big.next_actions.extend(checks());
+        let mut small = MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Call(1)]);
+        small.next_actions.extend(checks());
+        let mut table = Table::new(
+            vec![big, small].into_iter(),
+            100,
+            1,
+            BlindPolicy::NeverIncrease,
+        )
+        .with_double_board();
+
+        let mut cards = CardCollection::try_from("AhAdKhKd2c7d9sKs5c6h3hTc4cJd")
+            .unwrap()
+            .to_vec();
+        cards.reverse();
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::from(cards))
+            .unwrap()
+            .collect();
+
+        let card = |card: &str| CardCollection::try_from(card).unwrap()[0];
+        let second = |action: Action| Action::SecondBoard(Box::new(action));
+        assert_eq!(
+            actions[6..9],
+            [
+                Action::Check(0),
+                Action::DealFlop([card("2c"), card("7d"), card("9s")]),
+                second(Action::DealFlop([card("Ks"), card("5c"), card("6h")])),
+            ]
+        );
+        assert_eq!(
+            actions[actions.len() - 5..],
+            [
+                Action::DealRiver(card("4c")),
+                second(Action::DealRiver(card("Jd"))),
+                Action::Check(1),
+                Action::Check(0),
+                // the aces win the first board, the kings make trips on the second board
+                Action::Win(vec![(1, 2), (0, 2)]),
+            ]
+        );
+        assert_eq!(table.transparent_state.second_board.all_cards().len(), 5);
+
+        let state = crate::replay::replay(&actions).unwrap();
+        assert_eq!(state.player_stacks, [100, 100]);
+    }
+
+    /// Plays a round at a cash game table in which the dealer loses the whole stack.
+    fn bust_dealer(dealer: MockPlayer) -> Table<MockPlayer> {
+        let table = dealer_all_in(dealer).with_cash_game(CashGame {
+            min_buy_in: 50,
+            max_buy_in: 200,
+            top_up: Some(100),
+        });
+        play_dealer_all_in(table)
+    }
+
+    /// A table at which the dealer goes all-in in the first round, see `play_dealer_all_in`.
+    fn dealer_all_in(mut dealer: MockPlayer) -> Table<MockPlayer> {
+        dealer.next_actions.push_back(PlayerAction::AllIn(100));
+        let players = vec![
+            dealer,
+            MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Fold]), // small
+            MockPlayer::new(vec![PlayerAction::Blind(2), PlayerAction::AllIn(98)]), // big
+        ];
+        Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease)
+    }
+
+    /// Plays the round in which the dealer loses the whole stack.
+    fn play_dealer_all_in(mut table: Table<MockPlayer>) -> Table<MockPlayer> {
+        let mut cards = CardCollection::try_from("2c7dAhAdKhKd2s3s4h8c9d")
+            .unwrap()
+            .to_vec();
+        cards.reverse();
+        table
+            .play_one_round(CardCollection::from(cards))
+            .unwrap()
+            .for_each(drop);
+        assert_eq!(table.transparent_state.player_stacks, [0, 99, 201]);
+        table
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Event {
+        Action(Action),
+        RoundEnd(Vec<ChipCount>),
+        Bust(usize),
+    }
+
+    /// Records the events at a table.
+    struct Recorder(Rc<RefCell<Vec<Event>>>);
+
+    impl TableObserver for Recorder {
+        fn on_action(&mut self, action: &Action, _state: &TransparentState) {
+            self.0.borrow_mut().push(Event::Action(action.clone()));
+        }
+
+        fn on_round_end(&mut self, state: &TransparentState) {
+            let stacks = state.player_stacks.clone();
+            self.0.borrow_mut().push(Event::RoundEnd(stacks));
+        }
+
+        fn on_bust(&mut self, position: usize, _state: &TransparentState) {
+            self.0.borrow_mut().push(Event::Bust(position));
+        }
+    }
+
+    #[test]
+    fn test_observe_table() {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Blind(2)]), // dealer
+            MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Fold]),
+        ];
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease)
+            .with_observer(Recorder(Rc::clone(&events)));
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+
+        let mut expected: Vec<Event> = actions.into_iter().map(Event::Action).collect();
+        expected.push(Event::RoundEnd(vec![101, 99]));
+        assert_eq!(*events.borrow(), expected);
+    }
+
+    #[test]
+    fn test_players_observe_public_actions() {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Raise(10)]), // dealer
+            MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Fold]),
+            MockPlayer::new(vec![PlayerAction::Blind(2), PlayerAction::Fold]),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+
+        // the small blind folds and still observes the rest of the round but the hands of others
+        let observed = &table.players[1].as_ref().unwrap().observed;
+        let expected: Vec<&Action> = actions
+            .iter()
+            .filter(|action| !matches!(action, Action::DealHand(pos, _) if *pos != 1))
+            .collect();
+        assert_eq!(observed.iter().collect::<Vec<_>>(), expected);
+        assert_eq!(observed.len(), actions.len() - 2);
+        assert_eq!(observed.last(), Some(&Action::Win(vec![(0, 13)])));
+    }
+
+    #[test]
+    fn test_observe_bust() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let mut table =
+            bust_dealer(MockPlayer::new(vec![])).with_observer(Recorder(Rc::clone(&events)));
+        drop(table.play_one_round(CardCollection::default()).unwrap());
+        assert_eq!(*events.borrow(), [Event::Bust(0)]);
+
+        // without a cash game the player busts right after the round
+        events.borrow_mut().clear();
+        let table =
+            dealer_all_in(MockPlayer::new(vec![])).with_observer(Recorder(Rc::clone(&events)));
+        let table = play_dealer_all_in(table);
+        assert_eq!(
+            events.borrow()[events.borrow().len() - 2..],
+            [Event::RoundEnd(vec![0, 99, 201]), Event::Bust(0)]
+        );
+        assert!(table.players[0].as_ref().unwrap().busted);
+        assert!(!table.transparent_state.seated[0]);
+    }
+
+    #[test]
+    fn test_no_round_once_one_player_is_left() {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Blind(2), PlayerAction::AllIn(98)]), // dealer
+            MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::AllIn(99)]),
+        ];
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease)
+            .with_observer(Recorder(Rc::clone(&events)));
+        let mut cards = CardCollection::try_from("2c7dAhAd2s3s4h8c9d")
+            .unwrap()
+            .to_vec();
+        cards.reverse();
+        table
+            .play_one_round(CardCollection::from(cards))
+            .unwrap()
+            .for_each(drop);
+        assert_eq!(table.transparent_state.player_stacks, [200, 0]);
+        assert_eq!(events.borrow().last(), Some(&Event::Bust(1)));
+
+        assert!(table.play_one_round(CardCollection::default()).is_none());
+    }
+
+    #[test]
+    fn test_cash_game_rebuy_and_top_up() {
+        let mut dealer = MockPlayer::new(vec![]);
+        dealer.rebuy = Some(500);
+        let mut table = bust_dealer(dealer);
+
+        let round = table.play_one_round(CardCollection::default()).unwrap();
+        // the rebuy is capped, stacks exceeding the maximum buy-in are kept
+        assert_eq!(round.inspect_state().player_stacks, [200, 100, 201]);
+        drop(round);
+
+        let bought_in: Vec<ChipCount> = table.ledger().iter().map(|e| e.bought_in).collect();
+        assert_eq!(bought_in, [300, 101, 100]);
+        assert!(!table.players[0].as_ref().unwrap().busted);
+    }
+
+    #[test]
+    #[should_panic(expected = "Stacks should be valid buy-ins")]
+    fn test_cash_game_with_invalid_stacks() {
+        dealer_all_in(MockPlayer::new(vec![])).with_cash_game(CashGame {
+            min_buy_in: 200,
+            max_buy_in: 500,
+            top_up: None,
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Stack should be a valid buy-in")]
+    fn test_cash_game_seat_player_with_invalid_stack() {
+        let mut table = bust_dealer(MockPlayer::new(vec![])).with_max_seats(4);
+        table.seat_player(3, MockPlayer::new(vec![]), 201);
+    }
+
+    #[test]
+    fn test_cash_game_bust_and_cash_out() {
+        let mut table = bust_dealer(MockPlayer::new(vec![])).with_max_seats(4);
+        assert_eq!(table.cash_out(2), 201);
+        table.seat_player(
+            3,
+            MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Fold]),
+            100,
+        );
+        let big_blind = table.players[1].as_mut().unwrap();
+        big_blind.next_actions.push_back(PlayerAction::Blind(2));
+        for seat in [0, 2] {
+            table.players[seat].as_mut().unwrap().observed.clear();
+        }
+
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+
+        // neither the busted nor the cashed out player is dealt in
+        let dealt: Vec<usize> = actions
+            .iter()
+            .filter_map(|action| match action {
+                Action::DealHand(pos, _) => Some(*pos),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(dealt, [3, 1]);
+        assert_eq!(actions.last(), Some(&Action::Win(vec![(1, 3)])));
+        assert_eq!(table.transparent_state.player_stacks, [0, 101, 0, 99]);
+
+        assert!(table.players[0].as_ref().unwrap().busted);
+        // the players who left do not observe the round anymore
+        for seat in [0, 2] {
+            assert!(table.players[seat].as_ref().unwrap().observed.is_empty());
+        }
+        assert_eq!(table.ledger()[0].net(), -100);
+        assert_eq!(
+            table.ledger()[2],
+            LedgerEntry {
+                bought_in: 100,
+                cashed_out: 201,
+            }
+        );
+    }
+
+    #[test]
+    fn test_seat_and_remove_players() {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Fold]),
+            MockPlayer::new(vec![]),
+            MockPlayer::new(vec![PlayerAction::Blind(2)]),
+        ];
+        let mut table =
+            Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease).with_max_seats(5);
+        assert!(table.remove_player(1).is_some());
+        assert!(table.remove_player(1).is_none());
+        table.seat_player(3, MockPlayer::new(vec![PlayerAction::Fold]), 200);
+
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+
+        // the button moves to the player seated last, empty seats are not dealt in
+        let dealt: Vec<usize> = actions
+            .iter()
+            .filter_map(|action| match action {
+                Action::DealHand(pos, _) => Some(*pos),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(dealt, [0, 2, 3]);
+        assert_eq!(
+            actions[4..],
+            [
+                Action::Blind(0, 1),
+                Action::Blind(2, 2),
+                Action::Fold(3),
+                Action::Fold(0),
+                Action::Win(vec![(2, 3)]),
+            ]
+        );
+        assert_eq!(table.transparent_state.dealer_position, 3);
+        assert_eq!(table.players[3].as_ref().unwrap().position, Some(3));
+        assert_eq!(table.ledger()[1].cashed_out, 100);
+        assert_eq!(table.ledger()[3].bought_in, 200);
+
+        // the entry of the seat keeps the chips of the player who left it
+        table.seat_player(1, MockPlayer::new(vec![]), 50);
+        assert_eq!(
+            table.ledger()[1],
+            LedgerEntry {
+                bought_in: 150,
+                cashed_out: 100,
+            }
+        );
+
+        let state = crate::replay::replay(&actions).unwrap();
+        assert_eq!(state.player_stacks, [99, 0, 101, 200, 0]);
+    }
+
+    #[test]
+    fn test_sit_out_and_post_missed_blinds() {
+        let players = vec![
+            MockPlayer::new(vec![
+                PlayerAction::Fold,
+                PlayerAction::Blind(2),
+                PlayerAction::Blind(2),
+                PlayerAction::Fold,
+            ]),
+            MockPlayer::new(vec![
+                PlayerAction::Blind(1),
+                PlayerAction::Fold,
+                PlayerAction::Fold,
+                PlayerAction::Fold,
+            ]),
+            MockPlayer::new(vec![PlayerAction::Blind(3), PlayerAction::Bet(4)]),
+            MockPlayer::new(vec![
+                PlayerAction::Blind(2),
+                PlayerAction::Blind(1),
+                PlayerAction::Fold,
+                PlayerAction::Blind(1),
+                PlayerAction::Fold,
+            ]),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+        table.set_sitting_out(2, true);
+
+        // the player sitting out misses the big blind
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+        assert_eq!(actions[1], Action::SitOut(2));
+        assert_eq!(actions[5..7], [Action::Blind(1, 1), Action::Blind(3, 2)]);
+        assert_eq!(
+            table.transparent_state.owes_blinds,
+            [false, false, true, false]
+        );
+
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+        assert_eq!(actions[1], Action::SitOut(2));
+        // the player sitting out still observes the round
+        let player = table.players[2].as_ref().unwrap();
+        assert_eq!(player.observed.last(), actions.last());
+        assert!(player.summary.is_some());
+
+        // the returning player posts a dead small blind and a live big blind
+        table.set_sitting_out(2, false);
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+        assert_eq!(actions[1], Action::Return(2));
+        assert_eq!(
+            actions[6..],
+            [
+                Action::Blind(3, 1),
+                Action::Blind(0, 2),
+                Action::MissedBlinds(2, 3),
+                Action::Fold(1),
+                Action::Bet(2, 4),
+                Action::Fold(3),
+                Action::Fold(0),
+                Action::Win(vec![(2, 10)]),
+            ]
+        );
+        assert_eq!(table.transparent_state.owes_blinds, [false; 4]);
+        assert_eq!(table.transparent_state.player_stacks, [99, 99, 103, 99]);
+
+        let state = crate::replay::replay(&actions).unwrap();
+        assert_eq!(state.player_stacks, [99, 99, 103, 99]);
+    }
+
+    #[test]
+    fn test_sit_out_and_wait_for_big_blind() {
+        let players = vec![
+            MockPlayer::new(vec![
+                PlayerAction::Fold,
+                PlayerAction::Blind(2),
+                PlayerAction::Blind(1),
+                PlayerAction::Fold,
+                PlayerAction::Fold,
+            ]),
+            MockPlayer::new(vec![
+                PlayerAction::Blind(1),
+                PlayerAction::Fold,
+                PlayerAction::Fold,
+                PlayerAction::Blind(2),
+                PlayerAction::Blind(1),
+                PlayerAction::Fold,
+            ]),
+            MockPlayer::new(vec![PlayerAction::Blind(2)]),
+            MockPlayer::new(vec![
+                PlayerAction::Blind(2),
+                PlayerAction::Blind(1),
+                PlayerAction::Fold,
+                PlayerAction::Fold,
+                PlayerAction::Fold,
+            ]),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease)
+            .with_missed_blinds(MissedBlinds::WaitForBigBlind);
+        table.set_sitting_out(2, true);
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+        assert_eq!(actions[1], Action::SitOut(2));
+        table.set_sitting_out(2, false);
+
+        // the returning player is not dealt in until it is the player's turn to post the big blind
+        for dealer_position in [1, 3] {
+            let actions: Vec<Action> = table
+                .play_one_round(CardCollection::default())
+                .unwrap()
+                .collect();
+            assert_eq!(actions[1], Action::SitOut(2));
+            assert_eq!(table.transparent_state.dealer_position, dealer_position);
+        }
+
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+        assert_eq!(actions[1], Action::Return(2));
+        assert_eq!(actions[6..8], [Action::Blind(1, 1), Action::Blind(2, 2)]);
+        assert_eq!(actions.last(), Some(&Action::Win(vec![(2, 3)])));
+        assert_eq!(table.transparent_state.owes_blinds, [false; 4]);
+    }
+
+    #[test]
+    fn test_play_pot_limit() {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Raise(100)]), // dealer
+            MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Fold]), // small
+            MockPlayer::new(vec![PlayerAction::Blind(2), PlayerAction::Fold]), // big
+            MockPlayer::new(vec![PlayerAction::Call(2)]),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease)
+            .with_betting_structure(BettingStructure::PotLimit);
+        let mut round = table.play_one_round(CardCollection::default()).unwrap();
+        let actions: Vec<Action> = (&mut round).take(9).collect();
+
+        // the raise of the dealer is limited to call + pot after calling
+        assert_eq!(actions[7..], [Action::Call(3, 2), Action::Raise(0, 9)]);
+        if let Action::StartRound {
+            betting_structure, ..
+        } = &actions[0]
+        {
+            assert_eq!(*betting_structure, BettingStructure::PotLimit);
+        }
+    }
+
+    #[test]
+    fn test_play_antes_before_blinds() {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Ante(1), PlayerAction::Fold]), // dealer
+            MockPlayer::new(vec![
+                PlayerAction::Ante(1),
+                PlayerAction::Blind(1),
+                PlayerAction::Fold,
+            ]), // small
+            MockPlayer::new(vec![PlayerAction::Ante(1), PlayerAction::Blind(2)]), // big
+        ];
+        let mut table =
+            Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease).with_ante(1);
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+
+        // skip the meta information and card deals at the start
+        assert_eq!(
+            actions[4..],
+            [
+                Action::Ante(1, 1),
+                Action::Ante(2, 1),
+                Action::Ante(0, 1),
+                Action::Blind(1, 1),
+                Action::Blind(2, 2),
+                Action::Fold(0),
+                Action::Fold(1),
+                Action::Win(vec![(2, 3 + 1 + 2)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_play_ante_only() {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Ante(2), PlayerAction::Fold]), // dealer
+            MockPlayer::new(vec![PlayerAction::Ante(2), PlayerAction::Bet(2)]),
+            MockPlayer::new(vec![PlayerAction::Ante(2), PlayerAction::Fold]),
+        ];
+        let mut table =
+            Table::new(players.into_iter(), 100, 0, BlindPolicy::NeverIncrease).with_ante(2);
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+
+        // without blinds the player left of the dealer opens and the minimum bet is the ante
+        assert_eq!(
+            actions[4..],
+            [
+                Action::Ante(1, 2),
+                Action::Ante(2, 2),
+                Action::Ante(0, 2),
+                Action::Bet(1, 2),
+                Action::Fold(2),
+                Action::Fold(0),
+                Action::Win(vec![(1, 6 + 2)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_play_short_deck() {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Fold]), // dealer
+            MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Fold]), // small
+            MockPlayer::new(vec![PlayerAction::Blind(2)]), // big
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease)
+            .with_variant(GameVariant::ShortDeck);
+        let actions: Vec<Action> = table
+            .play_one_round(GameVariant::ShortDeck.deck())
+            .unwrap()
+            .collect();
+
+        assert_eq!(actions.last(), Some(&Action::Win(vec![(2, 3)])));
+        for position in 0..3 {
+            assert!(table
+                .transparent_state
+                .query_cards(position)
+                .iter()
+                .all(|card| card.value >= Value::Six));
+        }
+    }
+
+    #[test]
+    fn test_play_omaha() {
+        let players = vec![
+            MockPlayer::new(vec![PlayerAction::Fold]), // dealer
+            MockPlayer::new(vec![PlayerAction::Blind(1), PlayerAction::Fold]), // small
+            MockPlayer::new(vec![PlayerAction::Blind(2)]), // big
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease)
+            .with_variant(GameVariant::Omaha);
+        let actions: Vec<Action> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+
+        for action in actions[1..4].iter() {
+            match action {
+                Action::DealHand(_, cards) => assert_eq!(cards.len(), 4),
+                _ => panic!("Expected hole cards to be dealt"),
+            }
+        }
+        assert_eq!(table.transparent_state.query_cards(0).len(), 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_omaha_too_many_players() {
+        let players = (0..12).map(|_| MockPlayer::new(vec![]));
+        Table::new(players, 100, 1, BlindPolicy::NeverIncrease).with_variant(GameVariant::Omaha);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_stud_too_many_players() {
+        let players = (0..8).map(|_| MockPlayer::new(vec![]));
+        Table::new(players, 100, 1, BlindPolicy::NeverIncrease)
+            .with_variant(GameVariant::SevenCardStud);
+    }
+
     #[test]
     fn test_play_multiple_rounds() {
         let players = vec![
@@ -424,7 +1779,13 @@ mod tests {
         let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
 
         // implicit check, since mock players will panic if they cannot handle demands
-        let _: Vec<_> = table.play_one_round(CardCollection::default()).collect();
-        let _: Vec<_> = table.play_one_round(CardCollection::default()).collect();
+        let _: Vec<_> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
+        let _: Vec<_> = table
+            .play_one_round(CardCollection::default())
+            .unwrap()
+            .collect();
     }
 }
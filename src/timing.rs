@@ -0,0 +1,360 @@
+//! This module exposes a player wrapper which limits the time the wrapped player may take to decide.
+//!
+//! Decisions are taken on a worker thread, such that the game does not wait for players which take
+//! too long. Players without a time limit are called synchronously as usual.
+use crate::actions::{Action, PlayerAction};
+use crate::play::{Round, RoundCheckpoint};
+use crate::player::Player;
+use crate::state::TransparentState;
+use crate::summary::RoundSummary;
+use crate::ChipCount;
+
+use std::ops::DerefMut;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The time a player may take for decisions, see `TimedPlayer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeLimit {
+    /// The time the player may take for each decision.
+    pub per_decision: Duration,
+    /// Additional time shared by all the decisions of the player. Time exceeding `per_decision` is
+    /// taken from the time bank until it is used up.
+    pub time_bank: Duration,
+}
+
+/// Wraps a player which has to decide within the given `TimeLimit`.
+///
+/// Each decision is taken on a worker thread. If the player does not decide in time, the player
+/// checks if possible or folds otherwise. The late decision is discarded once it arrives. Rounds
+/// played using `Table::play_one_timed_round` record the fallback decision after an
+/// `Action::Timeout`.
+///
+/// Only `Player::act` is limited. Other callbacks wait for a late decision to finish, as the
+/// wrapped player is busy until then. Observed actions and round summaries never wait, they are
+/// delivered once the player is not busy anymore.
+#[derive(Debug)]
+pub struct TimedPlayer<P> {
+    player: Arc<Mutex<P>>,
+    time_limit: TimeLimit,
+    time_bank: Duration,
+    // notifications which arrived while the player was busy
+    pending: Vec<Notification>,
+}
+
+/// A notification of a `TimedPlayer`, see `Player::observe` and `Player::round_finished`.
+#[derive(Debug)]
+enum Notification {
+    Action(Action),
+    RoundFinished(RoundSummary),
+}
+
+impl Notification {
+    fn deliver(self, player: &mut impl Player) {
+        match self {
+            Notification::Action(action) => player.observe(&action),
+            Notification::RoundFinished(summary) => player.round_finished(&summary),
+        }
+    }
+}
+
+impl<P: Player + Send + 'static> TimedPlayer<P> {
+    /// Limit the time the given player may take for decisions.
+    pub fn new(player: P, time_limit: TimeLimit) -> Self {
+        Self {
+            player: Arc::new(Mutex::new(player)),
+            time_limit,
+            time_bank: time_limit.time_bank,
+            pending: Vec::new(),
+        }
+    }
+
+    /// The time remaining in the time bank of the player.
+    pub fn time_bank(&self) -> Duration {
+        self.time_bank
+    }
+
+    /// Refill the time bank of the player, i. e. before a new level of a tournament starts.
+    pub fn refill_time_bank(&mut self) {
+        self.time_bank = self.time_limit.time_bank;
+    }
+
+    /// Waits until the player is not busy anymore, the pending notifications are delivered first.
+    fn lock(&mut self) -> MutexGuard<'_, P> {
+        let mut player = self.player.lock().expect("Player should not panic");
+        for notification in self.pending.drain(..) {
+            notification.deliver(&mut *player);
+        }
+        player
+    }
+
+    /// Delivers the given notification right away unless the player is busy deciding.
+    fn notify(&mut self, notification: Notification) {
+        self.pending.push(notification);
+        match self.player.try_lock() {
+            Ok(mut player) => {
+                for notification in self.pending.drain(..) {
+                    notification.deliver(&mut *player);
+                }
+            }
+            Err(TryLockError::WouldBlock) => {}
+            Err(TryLockError::Poisoned(_)) => panic!("Player should not panic"),
+        }
+    }
+
+    /// Asks the player to decide on a worker thread, returns `None` if the player does not decide
+    /// in time.
+    fn act_in_time(
+        &mut self,
+        state: &TransparentState,
+        possible_actions: &[PlayerAction],
+    ) -> Option<PlayerAction> {
+        let (sender, receiver) = mpsc::channel();
+        let player = Arc::clone(&self.player);
+        let state = state.clone();
+        let actions = possible_actions.to_vec();
+        let pending = std::mem::take(&mut self.pending);
+        thread::spawn(move || {
+            let mut player = player.lock().expect("Player should not panic");
+            for notification in pending {
+                notification.deliver(&mut *player);
+            }
+            // the receiver is gone if the player timed out
+            let _ = sender.send(player.act(&state, &actions));
+        });
+
+        let start = Instant::now();
+        let limit = self.time_limit.per_decision + self.time_bank;
+        let decision = receiver.recv_timeout(limit).ok();
+        let exceeded = start.elapsed().saturating_sub(self.time_limit.per_decision);
+        self.time_bank = self.time_bank.saturating_sub(exceeded);
+        decision
+    }
+}
+
+/// The decision of a player who did not decide in time.
+fn fallback(possible_actions: &[PlayerAction]) -> PlayerAction {
+    if possible_actions.contains(&PlayerAction::Check) {
+        PlayerAction::Check
+    } else if possible_actions.contains(&PlayerAction::Fold) {
+        PlayerAction::Fold
+    } else {
+        possible_actions[0]
+    }
+}
+
+impl<P: Player + Send + 'static> Player for TimedPlayer<P> {
+    fn init(&mut self, position: usize, initial_stack: ChipCount) {
+        self.lock().init(position, initial_stack);
+    }
+
+    fn act(&mut self, state: &TransparentState, possible_actions: &[PlayerAction]) -> PlayerAction {
+        self.act_in_time(state, possible_actions)
+            .unwrap_or_else(|| fallback(possible_actions))
+    }
+
+    fn straddle(&mut self, state: &TransparentState, size: ChipCount) -> bool {
+        self.lock().straddle(state, size)
+    }
+
+    fn run_count(&mut self, state: &TransparentState, max_runs: usize) -> usize {
+        self.lock().run_count(state, max_runs)
+    }
+
+    fn rebuy(
+        &mut self,
+        state: &TransparentState,
+        min_buy_in: ChipCount,
+        max_buy_in: ChipCount,
+    ) -> Option<ChipCount> {
+        self.lock().rebuy(state, min_buy_in, max_buy_in)
+    }
+
+    fn sit_out(&mut self, state: &TransparentState, sitting_out: bool) -> bool {
+        self.lock().sit_out(state, sitting_out)
+    }
+
+    fn observe(&mut self, action: &Action) {
+        self.notify(Notification::Action(action.clone()));
+    }
+
+    fn round_finished(&mut self, summary: &RoundSummary) {
+        self.notify(Notification::RoundFinished(summary.clone()));
+    }
+
+    fn bust(&mut self) {
+        self.lock().bust();
+    }
+}
+
+/// A `Round` of `TimedPlayer`s which records the decisions of players who did not decide in time
+/// as `Action::Timeout` followed by the fallback decision.
+///
+/// Each step taken by `next` corresponds to one step taken in the round played.
+pub struct TimedRound<'a, P: Player + Send + 'static, T: DerefMut<Target = TransparentState>> {
+    round: Round<'a, TimedPlayer<P>, T>,
+}
+
+impl<'a, P, T> TimedRound<'a, P, T>
+where
+    P: Player + Send + 'static,
+    T: DerefMut<Target = TransparentState>,
+{
+    pub(crate) fn new(round: Round<'a, TimedPlayer<P>, T>) -> Self {
+        Self {
+            round: round.with_external_decisions(),
+        }
+    }
+
+    /// Clones this state into a `RoundCheckpoint`, see `Round::create_checkpoint`.
+    pub fn create_checkpoint(&self) -> RoundCheckpoint {
+        self.round.create_checkpoint()
+    }
+
+    /// Inspect the underlying `TransparentState` before stepping to the next action.
+    pub fn inspect_state(&self) -> &'_ TransparentState {
+        self.round.inspect_state()
+    }
+}
+
+impl<P, T> Iterator for TimedRound<'_, P, T>
+where
+    P: Player + Send + 'static,
+    T: DerefMut<Target = TransparentState>,
+{
+    type Item = Action;
+
+    fn next(&mut self) -> Option<Action> {
+        loop {
+            if let Some(action) = self.round.next() {
+                return Some(action);
+            }
+            // the round pauses whenever a player has to decide
+            let position = self.round.next_to_act()?;
+            let (players, state) = self.round.split();
+            let possible_actions = state.possible_actions(position);
+            match players[position].act_in_time(state, &possible_actions) {
+                Some(decision) => self.round.decide(decision),
+                None => self.round.decide_on_timeout(fallback(&possible_actions)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::CardCollection;
+    use crate::mock::MockPlayer;
+    use crate::table::{BlindPolicy, Table};
+
+    /// A player which takes the given time for each decision.
+    struct SlowPlayer {
+        player: MockPlayer,
+        delay: Duration,
+    }
+
+    impl Player for SlowPlayer {
+        fn init(&mut self, position: usize, initial_stack: ChipCount) {
+            self.player.init(position, initial_stack);
+        }
+
+        fn act(
+            &mut self,
+            state: &TransparentState,
+            possible_actions: &[PlayerAction],
+        ) -> PlayerAction {
+            if possible_actions.len() > 1 {
+                thread::sleep(self.delay);
+            }
+            self.player.act(state, possible_actions)
+        }
+
+        fn observe(&mut self, action: &Action) {
+            self.player.observe(action);
+        }
+
+        fn bust(&mut self) {}
+    }
+
+    fn timed_player(
+        actions: Vec<PlayerAction>,
+        delay: u64,
+        time_bank: u64,
+    ) -> TimedPlayer<SlowPlayer> {
+        let player = SlowPlayer {
+            player: MockPlayer::new(actions),
+            delay: Duration::from_millis(delay),
+        };
+        let time_limit = TimeLimit {
+            per_decision: Duration::from_millis(50),
+            time_bank: Duration::from_millis(time_bank),
+        };
+        TimedPlayer::new(player, time_limit)
+    }
+
+    #[test]
+    fn test_timeout_checks() {
+        let players = vec![
+            timed_player(vec![PlayerAction::Call(2)], 0, 0), // dealer
+            timed_player(vec![PlayerAction::Blind(1), PlayerAction::Call(1)], 0, 0),
+            timed_player(vec![PlayerAction::Blind(2), PlayerAction::Bet(4)], 1000, 0),
+        ];
+        let mut table = Table::new(players.into_iter(), 100, 1, BlindPolicy::NeverIncrease);
+        let actions: Vec<Action> = table
+            .play_one_timed_round(CardCollection::default())
+            .unwrap()
+            .take(10)
+            .collect();
+
+        // the big blind does not bet in time and checks instead
+        assert_eq!(
+            actions[4..],
+            [
+                Action::Blind(1, 1),
+                Action::Blind(2, 2),
+                Action::Call(0, 2),
+                Action::Call(1, 1),
+                Action::Timeout(2),
+                Action::Check(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_time_bank() {
+        let mut player = timed_player(vec![PlayerAction::Call(2); 2], 200, 250);
+        let state = TransparentState::new(1, 0, vec![100, 100]);
+        let possible_actions = [PlayerAction::Fold, PlayerAction::Call(2)];
+
+        // the time exceeding the time per decision is taken from the time bank
+        assert_eq!(
+            player.act_in_time(&state, &possible_actions),
+            Some(PlayerAction::Call(2))
+        );
+        assert!(player.time_bank() <= Duration::from_millis(100));
+
+        assert_eq!(player.act_in_time(&state, &possible_actions), None);
+        assert_eq!(player.time_bank(), Duration::ZERO);
+
+        player.refill_time_bank();
+        assert_eq!(player.time_bank(), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_observe_while_busy() {
+        let mut player = timed_player(vec![PlayerAction::Call(2)], 500, 0);
+        let state = TransparentState::new(1, 0, vec![100, 100]);
+        let possible_actions = [PlayerAction::Fold, PlayerAction::Call(2)];
+        assert_eq!(player.act(&state, &possible_actions), PlayerAction::Fold);
+
+        // the late decision is still taken, the game does not wait for it
+        let start = Instant::now();
+        player.observe(&Action::Fold(1));
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        assert_eq!(player.lock().player.observed, [Action::Fold(1)]);
+    }
+}
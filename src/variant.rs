@@ -0,0 +1,131 @@
+//! This module exposes the game variants supported, i. e. the rules which determine the number
+//! of hole cards and how hands are ranked.
+use crate::deck::{
+    cmp_short_deck, rank_omaha, rank_omaha_low, rank_short_deck, Card, CardCollection, LowRank,
+    Rank, Rankable,
+};
+use std::cmp::Ordering;
+
+/// Exposes variants to handle the different games of poker.
+///
+/// The variant of a table can be selected using `Table::with_variant`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum GameVariant {
+    /// Texas Hold'em. Each player is dealt two hole cards and the best five cards out of the
+    /// hole cards and the board make the hand.
+    #[default]
+    Holdem,
+    /// Omaha. Each player is dealt four hole cards and the hand has to be made of exactly two
+    /// hole cards and exactly three cards of the board.
+    Omaha,
+    /// Omaha Hi-Lo (eight or better). The hands are made just like in Omaha, but the pot is
+    /// split between the best high hand and the best qualifying ace-to-five low hand.
+    ///
+    /// If there is no qualifying low hand the best high hand wins the whole pot.
+    OmahaHiLo,
+    /// Short deck (6+) Hold'em. It is played like Texas Hold'em with a deck of 36 cards, i. e.
+    /// without the cards from two to five.
+    ///
+    /// A flush beats a full house and A-6-7-8-9 is the lowest straight.
+    ShortDeck,
+    /// Seven-card stud. There is no board, instead each player is dealt two cards face down,
+    /// four cards face up and a final card face down. The best five out of the seven cards
+    /// make the hand.
+    ///
+    /// Rounds of stud games are played using `Table::play_one_stud_round`. The player
+    /// showing the lowest up card brings in for the blind size, a full bet corresponds to the
+    /// big blind.
+    SevenCardStud,
+}
+
+impl GameVariant {
+    /// Returns the number of hole cards dealt to each player.
+    ///
+    /// In stud games these are the cards dealt face down before the first up card.
+    pub fn hole_cards(self) -> usize {
+        match self {
+            GameVariant::Holdem | GameVariant::ShortDeck | GameVariant::SevenCardStud => 2,
+            GameVariant::Omaha | GameVariant::OmahaHiLo => 4,
+        }
+    }
+
+    /// Returns a new deck containing all the cards used in this variant.
+    ///
+    /// The deck is not shuffled.
+    pub fn deck(self) -> CardCollection {
+        match self {
+            GameVariant::ShortDeck => CardCollection::short_deck(),
+            _ => CardCollection::default(),
+        }
+    }
+
+    /// Ranks the best hand which can be made of the given hole cards and the board.
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use poppy::deck::{CardCollection, Rank};
+    /// use poppy::GameVariant;
+    /// use std::convert::TryFrom;
+    ///
+    /// let hand = CardCollection::try_from("Ac2d3s4s").unwrap();
+    /// let board = CardCollection::try_from("QhJhTh9h8h").unwrap();
+    /// // the straight flush on the board may not be played in Omaha
+    /// assert!(GameVariant::Holdem.rank_hand(&hand[..], &board[..]) >= Rank::StraightFlush(0));
+    /// assert!(GameVariant::Omaha.rank_hand(&hand[..], &board[..]) < Rank::OnePair(0));
+    /// ```
+    pub fn rank_hand(self, hand: &[Card], board: &[Card]) -> Rank {
+        match self {
+            GameVariant::Holdem | GameVariant::SevenCardStud => {
+                let cards: Vec<Card> = hand.iter().chain(board.iter()).copied().collect();
+                cards.as_slice().rank()
+            }
+            GameVariant::Omaha | GameVariant::OmahaHiLo => rank_omaha(hand, board),
+            GameVariant::ShortDeck => {
+                let cards: Vec<Card> = hand.iter().chain(board.iter()).copied().collect();
+                rank_short_deck(&cards)
+            }
+        }
+    }
+
+    /// Compares two ranks created by `rank_hand` according to the hand rankings of this variant.
+    pub fn cmp_hands(self, a: &Rank, b: &Rank) -> Ordering {
+        match self {
+            GameVariant::ShortDeck => cmp_short_deck(a, b),
+            _ => a.cmp(b),
+        }
+    }
+
+    /// Ranks the best qualifying low hand which can be made of the given hole cards and the
+    /// board.
+    ///
+    /// Returns `None` if there is no qualifying low hand or if the pot is not split in this
+    /// variant.
+    pub fn rank_low(self, hand: &[Card], board: &[Card]) -> Option<LowRank> {
+        match self {
+            GameVariant::OmahaHiLo => rank_omaha_low(hand, board),
+            GameVariant::Holdem
+            | GameVariant::Omaha
+            | GameVariant::ShortDeck
+            | GameVariant::SevenCardStud => None,
+        }
+    }
+
+    /// Check whether the pot is split between the best high and the best low hand.
+    pub fn is_split(self) -> bool {
+        self == GameVariant::OmahaHiLo
+    }
+
+    /// Check whether this is a stud game, i. e. a game without community cards.
+    pub fn is_stud(self) -> bool {
+        self == GameVariant::SevenCardStud
+    }
+
+    /// Returns the number of cards required to deal a full round to the given number of players.
+    pub(crate) fn cards_required(self, num_players: usize) -> usize {
+        if self.is_stud() {
+            num_players * 7
+        } else {
+            num_players * self.hole_cards() + 5
+        }
+    }
+}